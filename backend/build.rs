@@ -0,0 +1,4 @@
+// 迁移脚本通过 sqlx::migrate! 编译进二进制，脚本增删改时需要重新编译
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+}
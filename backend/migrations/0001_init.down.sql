@@ -0,0 +1,8 @@
+-- 回滚 0001：删除核心表结构
+-- 说明: 表中数据一并删除
+
+DROP TABLE IF EXISTS task_resource;
+DROP TABLE IF EXISTS resource;
+DROP TABLE IF EXISTS task_link;
+DROP TABLE IF EXISTS task;
+DROP TABLE IF EXISTS account;
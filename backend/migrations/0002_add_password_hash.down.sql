@@ -0,0 +1,3 @@
+-- 回滚 0002：删除密码哈希字段
+
+ALTER TABLE account DROP COLUMN IF EXISTS password_hash;
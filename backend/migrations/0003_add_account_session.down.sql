@@ -0,0 +1,5 @@
+-- 回滚 0003：删除登录会话与刷新令牌
+-- 说明: 已登录的会话全部失效
+
+DROP TABLE IF EXISTS refresh_token;
+DROP TABLE IF EXISTS account_session;
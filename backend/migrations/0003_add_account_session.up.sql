@@ -0,0 +1,36 @@
+-- 登录会话与刷新令牌
+-- 创建时间: 2024-12-20
+-- 说明: access token 短期有效，refresh token 轮换使用并在服务端保存，支持注销与复用检测
+
+-- ============================================
+-- 1. account_session 表（登录会话 / 令牌家族）
+-- ============================================
+-- 一次登录对应一个会话，后续刷新出的所有 refresh token 都属于同一会话
+CREATE TABLE account_session (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,                   -- 会话（refresh token 家族）最长有效期
+    revoked_at TIMESTAMPTZ,                            -- 注销/复用检测后吊销时间
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    created_by UUID NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_by UUID NOT NULL
+);
+
+CREATE INDEX idx_account_session_account_id ON account_session(account_id);
+
+-- ============================================
+-- 2. refresh_token 表
+-- ============================================
+-- 只保存令牌的 SHA-256 摘要；used_at 非空表示已被轮换，再次出现即视为复用
+CREATE TABLE refresh_token (
+    id UUID PRIMARY KEY,
+    session_id UUID NOT NULL,
+    token_hash TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX idx_refresh_token_hash ON refresh_token(token_hash);
+CREATE INDEX idx_refresh_token_session ON refresh_token(session_id);
@@ -0,0 +1,11 @@
+-- 回滚 0004：删除家庭
+-- 说明: 账户与家庭的归属关系丢失，重新迁移后每个账户各自成为一个家庭；
+--       用户名恢复为全表唯一，已删除账户与现有账户重名时回滚会失败，需先处理重名数据
+
+DROP INDEX IF EXISTS idx_account_username;
+CREATE UNIQUE INDEX idx_account_username ON account(username) WHERE username IS NOT NULL;
+ALTER TABLE account ADD CONSTRAINT account_username_key UNIQUE (username);
+
+DROP INDEX IF EXISTS idx_account_family_id;
+ALTER TABLE account DROP COLUMN IF EXISTS family_id;
+DROP TABLE IF EXISTS family;
@@ -0,0 +1,39 @@
+-- 家庭（household）
+-- 创建时间: 2024-12-22
+-- 说明: 新增 family 表，account 通过 family_id 归属家庭；家长可管理同一家庭下的孩子账户；
+--       用户名只在未删除的账户中唯一，删除孩子账户后用户名可以再次使用
+
+-- ============================================
+-- 1. family 表（家庭）
+-- ============================================
+CREATE TABLE family (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,                                -- 家庭名称
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    created_by UUID NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_by UUID NOT NULL,
+    is_deleted BOOLEAN NOT NULL DEFAULT false
+);
+
+-- ============================================
+-- 2. account 归属家庭
+-- ============================================
+ALTER TABLE account ADD COLUMN family_id UUID;
+
+-- 已有账户各自成为一个家庭（家庭 ID 复用账户 ID）
+INSERT INTO family (id, name, created_at, created_by, updated_at, updated_by, is_deleted)
+SELECT id, nickname, now(), id, now(), id, false FROM account WHERE family_id IS NULL;
+
+UPDATE account SET family_id = id WHERE family_id IS NULL;
+
+ALTER TABLE account ALTER COLUMN family_id SET NOT NULL;
+
+CREATE INDEX idx_account_family_id ON account(family_id);
+
+-- ============================================
+-- 3. 用户名唯一性只约束未删除的账户
+-- ============================================
+ALTER TABLE account DROP CONSTRAINT account_username_key;
+DROP INDEX idx_account_username;
+CREATE UNIQUE INDEX idx_account_username ON account(username) WHERE username IS NOT NULL AND is_deleted = false;
@@ -0,0 +1,3 @@
+-- 回滚 0005：删除任务状态流转记录
+
+DROP TABLE IF EXISTS task_status_log;
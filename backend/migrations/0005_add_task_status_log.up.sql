@@ -0,0 +1,17 @@
+-- 任务状态流转记录
+-- 创建时间: 2024-12-24
+-- 说明: 记录每次状态变更（完成 / 重新打开 / 归档 / 取消归档）及操作人
+
+CREATE TABLE task_status_log (
+    id UUID PRIMARY KEY,
+    task_id UUID NOT NULL,
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    created_by UUID NOT NULL,                          -- 操作人
+
+    CONSTRAINT chk_task_status_log_from CHECK (from_status IN ('active', 'done', 'archived')),
+    CONSTRAINT chk_task_status_log_to CHECK (to_status IN ('active', 'done', 'archived'))
+);
+
+CREATE INDEX idx_task_status_log_task ON task_status_log(task_id, created_at);
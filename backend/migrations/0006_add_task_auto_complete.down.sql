@@ -0,0 +1,3 @@
+-- 回滚 0006：删除计划自动完成标记
+
+ALTER TABLE task DROP COLUMN IF EXISTS auto_complete;
@@ -0,0 +1,5 @@
+-- 计划自动完成
+-- 创建时间: 2024-12-25
+-- 说明: 计划（type='plan'）的 includes 子任务全部完成后自动标记为完成
+
+ALTER TABLE task ADD COLUMN auto_complete BOOLEAN NOT NULL DEFAULT false;
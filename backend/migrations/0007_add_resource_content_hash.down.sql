@@ -0,0 +1,6 @@
+-- 回滚 0007：删除资源内容哈希与大小
+-- 说明: 按账户统计的已用空间依赖 file_size，回滚后配额统计失效
+
+DROP INDEX IF EXISTS idx_resource_account_hash;
+ALTER TABLE resource DROP COLUMN IF EXISTS file_size;
+ALTER TABLE resource DROP COLUMN IF EXISTS content_hash;
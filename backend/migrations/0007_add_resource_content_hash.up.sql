@@ -0,0 +1,11 @@
+-- 资源内容去重与存储配额
+-- 创建时间: 2024-12-26
+-- 说明: 记录文件 SHA-256 与大小；同一账户内相同内容复用同一份存储对象，按账户统计已用空间
+
+ALTER TABLE resource ADD COLUMN content_hash TEXT;                  -- SHA-256（hex），历史数据为空
+ALTER TABLE resource ADD COLUMN file_size BIGINT NOT NULL DEFAULT 0; -- 文件大小（字节）
+
+-- 历史数据的文件大小只能从存储读取，迁移中无法回填，保持为 0：
+-- 这部分资源不计入配额，已用空间会偏小，直到用户删除后重新上传
+
+CREATE INDEX idx_resource_account_hash ON resource(account_id, content_hash);
@@ -0,0 +1,3 @@
+-- 回滚 0008：删除资源 MIME 类型
+
+ALTER TABLE resource DROP COLUMN IF EXISTS mime_type;
@@ -0,0 +1,5 @@
+-- 资源的实际 MIME 类型
+-- 创建时间: 2024-12-26
+-- 说明: 上传时按文件头识别出的类型，下载时作为 Content-Type，不再按文件名推断；历史数据为空，下载时按 application/octet-stream 处理
+
+ALTER TABLE resource ADD COLUMN mime_type TEXT;
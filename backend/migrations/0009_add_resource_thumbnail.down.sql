@@ -0,0 +1,4 @@
+-- 回滚 0009：删除资源缩略图字段
+-- 说明: 存储中已生成的缩略图文件不会删除
+
+ALTER TABLE resource DROP COLUMN IF EXISTS thumbnail_key;
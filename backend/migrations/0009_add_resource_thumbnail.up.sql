@@ -0,0 +1,5 @@
+-- 资源缩略图
+-- 创建时间: 2024-12-27
+-- 说明: 图片 / PDF 首页缩略图由上传后的后台任务生成，与原文件存放在同一存储后端；生成前为空
+
+ALTER TABLE resource ADD COLUMN thumbnail_key TEXT; -- 缩略图在存储中的 key
@@ -0,0 +1,3 @@
+-- 回滚 0010：删除任务草稿
+
+DROP TABLE IF EXISTS task_draft;
@@ -0,0 +1,31 @@
+-- AI 识别的任务草稿
+-- 创建时间: 2024-12-28
+-- 说明: 截图导入识别出的任务先存为草稿，用户编辑确认后才生成正式任务；每次识别为一个批次，position 为批次内顺序
+--       资源和任务平时只做软删除（is_deleted），外键只在物理清理时生效：资源清理时草稿一并删除，
+--       任务清理时草稿保留、task_id 置空；资源软删除后其草稿由查询过滤，不再可见或处理
+
+CREATE TABLE task_draft (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL,                          -- 任务归属账户（与来源资源一致）
+    resource_id UUID NOT NULL REFERENCES resource(id) ON DELETE CASCADE, -- 来源资源
+    batch_id UUID NOT NULL,                            -- 识别批次
+    position INT NOT NULL,                             -- 批次内顺序
+    title TEXT NOT NULL,
+    description TEXT,
+    type TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    due_date TIMESTAMPTZ,
+    status TEXT NOT NULL DEFAULT 'pending',            -- pending / accepted / rejected
+    task_id UUID REFERENCES task(id) ON DELETE SET NULL, -- 确认后生成的任务
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    created_by UUID NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_by UUID NOT NULL,
+
+    CONSTRAINT chk_task_draft_type CHECK (type IN ('homework', 'practice', 'preview', 'review', 'plan')),
+    CONSTRAINT chk_task_draft_subject CHECK (subject IN ('math', 'chinese', 'english', 'physics', 'chemistry', 'programming', 'other')),
+    CONSTRAINT chk_task_draft_status CHECK (status IN ('pending', 'accepted', 'rejected'))
+);
+
+CREATE INDEX idx_task_draft_account_status ON task_draft(account_id, status);
+CREATE INDEX idx_task_draft_resource ON task_draft(resource_id, created_at);
@@ -0,0 +1,4 @@
+-- 回滚 0011：删除后台任务队列
+-- 说明: 未执行的任务一并删除，回滚前先停止 worker（JOB_WORKERS=0）
+
+DROP TABLE IF EXISTS job;
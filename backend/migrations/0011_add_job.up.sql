@@ -0,0 +1,27 @@
+-- 后台任务队列
+-- 创建时间: 2024-12-29
+-- 说明: 耗时操作（AI 识别、缩略图等）入队后由 worker 异步执行；worker 用 FOR UPDATE SKIP LOCKED 抢占任务，
+--       失败按指数退避重试，超过最大次数进入 dead 状态（死信），可手动重新入队
+
+CREATE TABLE job (
+    id UUID PRIMARY KEY,
+    kind TEXT NOT NULL,                                -- 任务类型，见 JobPayload
+    payload JSONB NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued',             -- queued / running / succeeded / dead
+    attempts INT NOT NULL DEFAULT 0,                   -- 已执行次数
+    max_attempts INT NOT NULL,
+    run_at TIMESTAMPTZ NOT NULL DEFAULT now(),         -- 最早执行时间（重试时推后）
+    locked_at TIMESTAMPTZ,                             -- worker 开始执行的时间
+    locked_by TEXT,                                    -- 执行中的 worker
+    last_error TEXT,
+    result JSONB,
+    account_id UUID,                                   -- 发起账户，查询任务状态时按此校验
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    finished_at TIMESTAMPTZ,
+
+    CONSTRAINT chk_job_status CHECK (status IN ('queued', 'running', 'succeeded', 'dead'))
+);
+
+CREATE INDEX idx_job_queued ON job(run_at) WHERE status = 'queued';
+CREATE INDEX idx_job_account ON job(account_id, created_at);
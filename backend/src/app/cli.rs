@@ -0,0 +1,111 @@
+pub const USAGE: &str = "\
+用法:
+  learnnest [serve]                       启动 HTTP 服务和后台任务 worker（默认）
+  learnnest --check-config                校验配置并输出生效的配置后退出
+  learnnest migrate up                    执行全部未应用的迁移
+  learnnest migrate status                查看各迁移版本的执行状态
+  learnnest migrate down-to <版本> [--yes]  回滚到指定版本（0 表示全部回滚），生产环境需加 --yes 确认";
+
+/// 命令行子命令
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Serve,
+    CheckConfig,
+    Migrate(MigrateCommand),
+    Help,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrateCommand {
+    Up,
+    Status,
+    DownTo { version: i64, confirmed: bool },
+}
+
+/// 解析命令行参数（不含程序名），无法识别时返回错误说明
+pub fn parse_args<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let args: Vec<String> = args.into_iter().collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        [] | ["serve"] => Ok(Command::Serve),
+        ["--check-config"] | ["serve", "--check-config"] => Ok(Command::CheckConfig),
+        ["help" | "-h" | "--help"] => Ok(Command::Help),
+        ["migrate", "up"] => Ok(Command::Migrate(MigrateCommand::Up)),
+        ["migrate", "status"] => Ok(Command::Migrate(MigrateCommand::Status)),
+        ["migrate", "down-to", version, rest @ ..] => {
+            let version = version
+                .parse::<i64>()
+                .ok()
+                .filter(|v| *v >= 0)
+                .ok_or_else(|| format!("无效的迁移版本: {}", version))?;
+            let confirmed = match rest {
+                [] => false,
+                ["--yes"] => true,
+                _ => return Err(format!("无法识别的参数: {}", rest.join(" "))),
+            };
+            Ok(Command::Migrate(MigrateCommand::DownTo {
+                version,
+                confirmed,
+            }))
+        }
+        ["migrate", "down-to"] => Err("migrate down-to 需指定目标版本".into()),
+        ["migrate", ..] => Err("migrate 子命令仅支持 up / status / down-to".into()),
+        _ => Err(format!("无法识别的参数: {}", args.join(" "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, String> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    fn down_to(version: i64, confirmed: bool) -> Command {
+        Command::Migrate(MigrateCommand::DownTo { version, confirmed })
+    }
+
+    #[test]
+    fn recognized_commands() {
+        let cases = [
+            ("", Command::Serve),
+            ("serve", Command::Serve),
+            ("--check-config", Command::CheckConfig),
+            ("serve --check-config", Command::CheckConfig),
+            ("--help", Command::Help),
+            ("migrate up", Command::Migrate(MigrateCommand::Up)),
+            ("migrate status", Command::Migrate(MigrateCommand::Status)),
+            ("migrate down-to 3", down_to(3, false)),
+            ("migrate down-to 0 --yes", down_to(0, true)),
+        ];
+        for (args, expected) in cases {
+            assert_eq!(parse(args), Ok(expected), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        let cases = [
+            ("migrate down-to", "需指定目标版本"),
+            ("migrate down-to -1", "无效的迁移版本: -1"),
+            ("migrate down-to abc", "无效的迁移版本: abc"),
+            ("migrate down-to 3 --force", "无法识别的参数: --force"),
+            (
+                "migrate down-to 3 --yes extra",
+                "无法识别的参数: --yes extra",
+            ),
+            ("migrate", "仅支持 up / status / down-to"),
+            ("migrate up now", "仅支持 up / status / down-to"),
+            ("serve --port 80", "无法识别的参数: serve --port 80"),
+        ];
+        for (args, message) in cases {
+            let err = parse(args).unwrap_err();
+            assert!(err.contains(message), "{:?}: {}", args, err);
+        }
+    }
+}
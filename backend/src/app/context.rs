@@ -1,37 +1,102 @@
 use crate::app::from_pool::FromPool;
+use crate::error::AppError;
+use crate::llm::LlmProvider;
+use crate::ocr::OcrEngine;
 use crate::repository::account::AccountRepository;
+use crate::repository::family::FamilyRepository;
+use crate::repository::job::JobRepository;
+use crate::repository::resource::ResourceRepository;
+use crate::repository::session::SessionRepository;
 use crate::repository::task::TaskRepository;
+use crate::repository::task_draft::TaskDraftRepository;
+use crate::repository::task_link::TaskLinkRepository;
+use crate::repository::task_resource::TaskResourceRepository;
 use crate::service::account::AccountService;
+use crate::service::extraction::ExtractionService;
+use crate::service::family::FamilyService;
+use crate::service::job::JobService;
+use crate::service::ocr::OcrService;
+use crate::service::resource::ResourceService;
+use crate::service::session::SessionService;
 use crate::service::task::TaskService;
-use sqlx::PgPool;
+use crate::service::task_draft::TaskDraftService;
+use crate::service::task_link::TaskLinkService;
+use crate::service::task_resource::TaskResourceService;
+use crate::storage::Storage;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct Repos {
     pub task: TaskRepository,
     pub account: AccountRepository,
+    pub session: SessionRepository,
+    pub family: FamilyRepository,
+    pub task_link: TaskLinkRepository,
+    pub resource: ResourceRepository,
+    pub task_resource: TaskResourceRepository,
+    pub task_draft: TaskDraftRepository,
+    pub job: JobRepository,
     // 其它的 repo
+    pool: PgPool,
 }
 impl Repos {
     pub fn new(pool: PgPool) -> Self {
         Self {
             task: TaskRepository::from_pool(pool.clone()),
             account: AccountRepository::from_pool(pool.clone()),
+            session: SessionRepository::from_pool(pool.clone()),
+            family: FamilyRepository::from_pool(pool.clone()),
+            task_link: TaskLinkRepository::from_pool(pool.clone()),
+            resource: ResourceRepository::from_pool(pool.clone()),
+            task_resource: TaskResourceRepository::from_pool(pool.clone()),
+            task_draft: TaskDraftRepository::from_pool(pool.clone()),
+            job: JobRepository::from_pool(pool.clone()),
+            pool,
         }
     }
+
+    /// 开启跨 repository 的事务，传给各 repository 接收 &mut PgConnection 的方法
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>, AppError> {
+        Ok(self.pool.begin().await?)
+    }
 }
 
 #[derive(Clone)]
 pub struct Services {
     pub task: TaskService,
     pub account: AccountService,
+    pub session: SessionService,
+    pub family: FamilyService,
+    pub task_link: TaskLinkService,
+    pub resource: ResourceService,
+    pub task_resource: TaskResourceService,
+    pub extraction: ExtractionService,
+    pub task_draft: TaskDraftService,
+    pub job: JobService,
+    pub ocr: OcrService,
     // 其它的 service
 }
 
 impl Services {
-    pub fn new(repos: Repos) -> Self {
+    pub fn new(
+        repos: Repos,
+        storage: Arc<dyn Storage>,
+        llm: Option<Arc<dyn LlmProvider>>,
+        ocr: Option<Arc<dyn OcrEngine>>,
+    ) -> Self {
         Self {
             task: TaskService::new(repos.clone()),
             account: AccountService::new(repos.clone()),
+            session: SessionService::new(repos.clone()),
+            family: FamilyService::new(repos.clone()),
+            task_link: TaskLinkService::new(repos.clone()),
+            resource: ResourceService::new(repos.clone(), storage.clone()),
+            task_resource: TaskResourceService::new(repos.clone()),
+            extraction: ExtractionService::new(repos.clone(), storage.clone(), llm),
+            task_draft: TaskDraftService::new(repos.clone()),
+            job: JobService::new(repos.clone()),
+            ocr: OcrService::new(repos.clone(), storage.clone(), ocr),
         }
     }
 }
@@ -39,14 +104,20 @@ impl Services {
 #[derive(Clone)]
 pub struct AppContext {
     pub pool: PgPool,
+    #[expect(dead_code)]
     pub repos: Repos,
     pub services: Services,
 }
 
 impl AppContext {
-    pub fn new(pool: PgPool) -> Self {
+    pub fn new(
+        pool: PgPool,
+        storage: Arc<dyn Storage>,
+        llm: Option<Arc<dyn LlmProvider>>,
+        ocr: Option<Arc<dyn OcrEngine>>,
+    ) -> Self {
         let repos = Repos::new(pool.clone());
-        let services = Services::new(repos.clone());
+        let services = Services::new(repos.clone(), storage, llm, ocr);
         Self {
             pool,
             repos,
@@ -1,2 +1,4 @@
+pub mod cli;
 pub mod context;
 pub mod from_pool;
+pub mod shutdown;
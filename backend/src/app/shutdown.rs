@@ -0,0 +1,30 @@
+/// 等待退出信号：Ctrl+C（SIGINT），Unix 下还包括 SIGTERM（docker stop / compose down）
+pub async fn wait_for_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!(err = ?e, "监听 Ctrl+C 失败");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                tracing::error!(err = ?e, "监听 SIGTERM 失败");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => tracing::info!("收到 SIGINT"),
+        _ = terminate => tracing::info!("收到 SIGTERM"),
+    }
+}
@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+// 敏感配置项，报告和 --check-config 输出中脱敏
+const SECRET_KEYS: [&str; 6] = [
+    "JWT_SECRET",
+    "DOWNLOAD_SIGNING_KEY",
+    "DATABASE_PASSWORD",
+    "OSS_ACCESS_KEY_ID",
+    "OSS_ACCESS_KEY_SECRET",
+    "LLM_API_KEY",
+];
+const REDACTED: &str = "******";
+
+/// 一项配置错误：键、取值（已脱敏）、原因
+#[derive(Debug)]
+pub struct ConfigIssue {
+    pub key: String,
+    pub value: Option<String>, // 未设置时为空
+    pub reason: String,
+}
+
+/// 启动时收集到的全部配置错误，一次性报告
+#[derive(Debug)]
+pub struct ConfigReport {
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "配置校验失败（{} 项）：", self.issues.len())?;
+        for issue in &self.issues {
+            match &issue.value {
+                Some(value) => writeln!(f, "  - {}={:?}：{}", issue.key, value, issue.reason)?,
+                None => writeln!(f, "  - {}（未设置）：{}", issue.key, issue.reason)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigReport {}
+
+/// 生效的配置项（已脱敏），--check-config 输出
+#[derive(Debug)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: String,
+    pub is_default: bool, // 未设置，使用内置默认值
+}
+
+impl fmt::Display for ConfigEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.key, self.value)?;
+        if self.is_default {
+            write!(f, "  # 默认值")?;
+        }
+        Ok(())
+    }
+}
+
+/// 读取环境变量并记录：解析失败不立即返回，而是记入 issues 后继续，最后统一报告
+pub(crate) struct EnvReader {
+    vars: HashMap<String, String>,
+    issues: Vec<ConfigIssue>,
+    entries: Vec<ConfigEntry>,
+}
+
+impl EnvReader {
+    pub fn new(vars: HashMap<String, String>) -> Self {
+        Self {
+            vars,
+            issues: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// 已设置时返回取值
+    pub fn optional(&mut self, key: &str) -> Option<String> {
+        let value = self.vars.get(key).cloned()?;
+        self.record(key, &value, false);
+        Some(value)
+    }
+
+    pub fn string(&mut self, key: &str, default: &str) -> String {
+        self.optional(key).unwrap_or_else(|| {
+            self.record(key, default, true);
+            default.to_string()
+        })
+    }
+
+    /// 必填项，未设置或为空时记录错误并返回空字符串
+    pub fn required(&mut self, key: &str) -> String {
+        match self.optional(key).filter(|v| !v.trim().is_empty()) {
+            Some(value) => value,
+            None => {
+                self.issues.push(ConfigIssue {
+                    key: key.to_string(),
+                    value: None,
+                    reason: "必须设置".into(),
+                });
+                String::new()
+            }
+        }
+    }
+
+    /// 解析为数值等类型，失败时记录错误并返回默认值
+    pub fn parse<T>(&mut self, key: &str, default: T) -> T
+    where
+        T: FromStr + fmt::Display,
+        T::Err: fmt::Display,
+    {
+        let Some(raw) = self.optional(key) else {
+            self.record(key, &default.to_string(), true);
+            return default;
+        };
+        match raw.trim().parse::<T>() {
+            Ok(value) => value,
+            Err(e) => {
+                self.invalid(key, format!("无法解析为 {}: {}", short_type_name::<T>(), e));
+                default
+            }
+        }
+    }
+
+    /// 可选的数值项，未设置或解析失败时返回 None（失败记录错误）
+    pub fn parse_optional<T>(&mut self, key: &str) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let raw = self.optional(key)?;
+        match raw.trim().parse::<T>() {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.invalid(key, format!("无法解析为 {}: {}", short_type_name::<T>(), e));
+                None
+            }
+        }
+    }
+
+    /// 数值下限校验
+    pub fn parse_min<T>(&mut self, key: &str, default: T, min: T) -> T
+    where
+        T: FromStr + fmt::Display + PartialOrd,
+        T::Err: fmt::Display,
+    {
+        let value = self.parse(key, default);
+        if value < min {
+            self.invalid(key, format!("不能小于 {}", min));
+        }
+        value
+    }
+
+    /// 端口号，需在 1-65535 之间
+    pub fn port(&mut self, key: &str, default: u16) -> u16 {
+        let value = self.parse::<i64>(key, default.into());
+        match u16::try_from(value) {
+            Ok(port) if port > 0 => port,
+            _ => {
+                self.invalid(key, "端口需在 1-65535 之间".into());
+                default
+            }
+        }
+    }
+
+    pub fn bool(&mut self, key: &str, default: bool) -> bool {
+        match self.optional(key).as_deref().map(str::trim) {
+            None => {
+                self.record(key, &default.to_string(), true);
+                default
+            }
+            Some("true" | "1") => true,
+            Some("false" | "0" | "") => false,
+            Some(_) => {
+                self.invalid(key, "仅支持 true / false".into());
+                default
+            }
+        }
+    }
+
+    /// 枚举取值，options 第一项为默认值；空字符串视为未设置
+    pub fn choice<T: Copy>(&mut self, key: &str, options: &[(&str, T)]) -> T {
+        let (default_name, default) = options[0];
+        let raw = self.string(key, default_name);
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return default;
+        }
+        match options.iter().find(|(name, _)| *name == raw) {
+            Some((_, value)) => *value,
+            None => {
+                let names: Vec<&str> = options.iter().map(|(name, _)| *name).collect();
+                self.invalid(key, format!("仅支持 {}", names.join(" / ")));
+                default
+            }
+        }
+    }
+
+    /// 记录一项错误，取值取自当前环境变量
+    pub fn invalid(&mut self, key: &str, reason: String) {
+        self.issues.push(ConfigIssue {
+            key: key.to_string(),
+            value: self.vars.get(key).map(|v| redact(key, v)),
+            reason,
+        });
+    }
+
+    /// 记录由其它配置推导出的生效值（如拼接的 DATABASE_URL）
+    pub fn derived(&mut self, key: &str, value: &str) {
+        self.record(key, value, false);
+    }
+
+    pub fn finish(self) -> Result<Vec<ConfigEntry>, ConfigReport> {
+        if self.issues.is_empty() {
+            Ok(self.entries)
+        } else {
+            Err(ConfigReport {
+                issues: self.issues,
+            })
+        }
+    }
+
+    // 同一个键只记录一次
+    fn record(&mut self, key: &str, value: &str, is_default: bool) {
+        if self.entries.iter().any(|e| e.key == key) {
+            return;
+        }
+        self.entries.push(ConfigEntry {
+            key: key.to_string(),
+            value: redact(key, value),
+            is_default,
+        });
+    }
+}
+
+// 敏感项整体隐藏；连接串只隐藏密码部分
+fn redact(key: &str, value: &str) -> String {
+    if value.is_empty() {
+        return String::new();
+    }
+    if SECRET_KEYS.contains(&key) {
+        return REDACTED.to_string();
+    }
+    if key == "DATABASE_URL" {
+        if let Some((scheme, rest)) = value.split_once("://") {
+            if let Some((credentials, host)) = rest.rsplit_once('@') {
+                if let Some((user, _)) = credentials.split_once(':') {
+                    return format!("{}://{}:{}@{}", scheme, user, REDACTED, host);
+                }
+            }
+        }
+    }
+    value.to_string()
+}
+
+fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reader(vars: &[(&str, &str)]) -> EnvReader {
+        EnvReader::new(
+            vars.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn redact_hides_secrets_and_url_password() {
+        assert_eq!(redact("LLM_API_KEY", "sk-123"), REDACTED);
+        assert_eq!(redact("JWT_SECRET", ""), "");
+        assert_eq!(
+            redact("DATABASE_URL", "postgres://app:p@ss@db:5432/app"),
+            "postgres://app:******@db:5432/app"
+        );
+        // 没有密码的连接串原样输出
+        assert_eq!(
+            redact("DATABASE_URL", "postgres://app@db/app"),
+            "postgres://app@db/app"
+        );
+        assert_eq!(redact("SERVER_HOST", "0.0.0.0"), "0.0.0.0");
+    }
+
+    #[test]
+    fn finish_reports_every_issue() {
+        let mut r = reader(&[("REQUEST_TIMEOUT", "0"), ("JOB_WORKERS", "many")]);
+        r.parse_min("REQUEST_TIMEOUT", 30u64, 1);
+        r.parse("JOB_WORKERS", 2usize);
+        r.required("JWT_SECRET");
+
+        let report = r.finish().unwrap_err();
+        let output = report.to_string();
+        assert!(output.starts_with("配置校验失败（3 项）"));
+        assert!(
+            output.contains(r#"REQUEST_TIMEOUT="0"：不能小于 1"#),
+            "{}",
+            output
+        );
+        assert!(output.contains(r#"JOB_WORKERS="many"：无法解析为 usize"#));
+        assert!(output.contains("JWT_SECRET（未设置）：必须设置"));
+    }
+
+    #[test]
+    fn finish_returns_entries_once_per_key() {
+        let mut r = reader(&[("SERVER_PORT", "8080")]);
+        assert_eq!(r.port("SERVER_PORT", 9000), 8080);
+        assert_eq!(r.string("SERVER_PORT", "9000"), "8080");
+        assert_eq!(r.string("SERVER_HOST", "0.0.0.0"), "0.0.0.0");
+
+        let entries = r.finish().unwrap();
+        let entries: Vec<String> = entries.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            entries,
+            ["SERVER_PORT=8080", "SERVER_HOST=0.0.0.0  # 默认值"]
+        );
+    }
+}
@@ -2,15 +2,16 @@ use std::time::Duration;
 
 use sqlx::{PgPool, postgres::PgPoolOptions};
 
-use crate::config::AppConfig;
+use crate::config::DatabaseConfig;
 
 
-pub async fn create_pool(config: &AppConfig) -> Result<PgPool, sqlx::Error> {
+pub async fn create_pool(config: &DatabaseConfig) -> Result<PgPool, sqlx::Error> {
     let pool = PgPoolOptions::new()
-        .max_connections(config.db_max_conn)
-        .acquire_timeout(Duration::from_secs(config.db_connect_timeout_secs))
-        .idle_timeout(Duration::from_secs(config.db_idle_timeout))
-        .connect(&config.db_url)
+        .max_connections(config.max_conn)
+        .min_connections(config.min_conn)
+        .acquire_timeout(Duration::from_secs(config.connect_timeout_secs))
+        .idle_timeout(Duration::from_secs(config.idle_timeout))
+        .connect(&config.url)
         .await?;
 
     Ok(pool)
@@ -19,6 +19,28 @@ pub enum AppError {
     #[error("未授权访问")]
     Unauthorized,
 
+    #[error("无权限执行此操作")]
+    Forbidden,
+
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("存储空间不足：已使用 {used} 字节，本次 {requested} 字节，上限 {quota} 字节")]
+    QuotaExceeded {
+        used: i64,
+        requested: i64,
+        quota: i64,
+    },
+
+    #[error("文件过大，上限 {0} 字节")]
+    PayloadTooLarge(usize),
+
+    #[error("不支持的文件类型: {0}")]
+    UnsupportedMediaType(String),
+
+    #[error("{0}")]
+    Upstream(String), // 外部服务（如 AI 服务）调用失败
+
     // === 内部错误（统一提示）===
     #[error("系统繁忙，请稍后重试")]
     Internal,
@@ -35,6 +57,13 @@ impl From<sqlx::Error> for AppError {
     fn from(err: sqlx::Error) -> Self {
         match err {
             sqlx::Error::RowNotFound => AppError::NotFound,
+            // CHECK 约束兜底：枚举值等非法输入返回 400 而不是 500
+            sqlx::Error::Database(ref db_err) if db_err.is_check_violation() => {
+                AppError::BadRequest(format!(
+                    "字段取值不合法: {}",
+                    db_err.constraint().unwrap_or_default()
+                ))
+            }
             _ => {
                 tracing::error!("数据库错误: {:?}", err);
                 AppError::Internal
@@ -58,6 +87,12 @@ impl Writer for AppError {
             AppError::NotFound => StatusCode::NOT_FOUND,
             AppError::PasswordError => StatusCode::UNAUTHORIZED,
             AppError::Unauthorized => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden => StatusCode::FORBIDDEN,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::QuotaExceeded { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            AppError::Upstream(_) => StatusCode::BAD_GATEWAY,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
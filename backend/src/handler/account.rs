@@ -1,10 +1,10 @@
 use crate::error::AppError;
 use crate::handler::{DepotExt, RequestExt};
-use crate::model::account::{AccountResponse, Claims, LoginRequest, LoginResponse};
+use crate::model::account::{LoginRequest, LoginResponse};
+use crate::model::session::{RefreshRequest, TokenResponse};
 use crate::response::{ApiResponse, ApiResult};
 use argon2::password_hash::Error;
 use argon2::{Argon2, PasswordHash, PasswordVerifier};
-use jsonwebtoken::{EncodingKey, Header};
 use salvo::writing::Json;
 use salvo::{handler, Depot, Request};
 
@@ -16,7 +16,7 @@ pub async fn login(req: &mut Request, depot: &mut Depot) -> ApiResult<LoginRespo
     let ctx = depot.app_context()?;
     let account = ctx.services.account.get(login_req.username).await?;
 
-    let hashed_pwd = account.password_hash.ok_or(AppError::Internal)?;
+    let hashed_pwd = account.password_hash.clone().ok_or(AppError::Internal)?;
 
     // 3. 验证密码（Argon2 verify）
     let hasher = PasswordHash::new(&hashed_pwd).map_err(|e| {
@@ -30,30 +30,43 @@ pub async fn login(req: &mut Request, depot: &mut Depot) -> ApiResult<LoginRespo
             _ => AppError::Internal,
         })?;
 
-    // 4. 生成 JWT token
-    let add_date = chrono::Utc::now() + chrono::Duration::days(7);
-    let claims = Claims {
-        sub: account.id.to_string(),
-        exp: add_date.timestamp() as usize,
-    };
-
+    // 4. 开启会话，签发 access token + refresh token
     let app_config = depot.app_config()?;
-    let token = jsonwebtoken::encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(&app_config.jwt_secret.as_bytes()),
-    )
-    .map_err(|_| AppError::Internal)?;
+    let tokens = ctx.services.session.start(app_config, &account).await?;
 
     // 5. 返回响应
     let login_resp = LoginResponse {
-        access_token: token,
-        account: AccountResponse {
-            id: account.id,
-            username: account.username,
-            nickname: account.nickname,
-        },
+        access_token: tokens.access_token,
+        refresh_token: tokens.refresh_token,
+        expires_in: tokens.expires_in,
+        account: account.try_into()?,
     };
 
     Ok(Json(ApiResponse::success(login_resp)))
 }
+
+#[handler]
+pub async fn refresh(req: &mut Request, depot: &mut Depot) -> ApiResult<TokenResponse> {
+    let refresh_req = req.parse_request_body::<RefreshRequest>().await?;
+
+    let ctx = depot.app_context()?;
+    let app_config = depot.app_config()?;
+    let tokens = ctx
+        .services
+        .session
+        .refresh(app_config, &refresh_req.refresh_token)
+        .await?;
+
+    Ok(Json(ApiResponse::success(tokens.into())))
+}
+
+#[handler]
+pub async fn logout(depot: &mut Depot) -> ApiResult<()> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let session_id = depot.current_session_id()?;
+
+    ctx.services.session.revoke(session_id, account_id).await?;
+
+    Ok(Json(ApiResponse::ok()))
+}
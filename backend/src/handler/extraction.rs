@@ -0,0 +1,29 @@
+use salvo::{handler, writing::Json, Depot, Request};
+
+use crate::error::AppError;
+use crate::handler::{DepotExt, RequestExt};
+use crate::model::job::{JobPayload, JobResponse};
+use crate::response::{ApiResponse, ApiResult};
+
+/// 识别资源（作业截图）中的任务：提交后台任务后立即返回，
+/// 完成后任务结果中带 draft_ids，草稿通过 /api/task-drafts 查看和确认
+#[handler]
+pub async fn extract_tasks(req: &mut Request, depot: &mut Depot) -> ApiResult<JobResponse> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let resource_id = req.require_uuid_param("id")?;
+
+    if !ctx.services.extraction.is_enabled() {
+        return Err(AppError::BadRequest("未启用 AI 服务".into()));
+    }
+    // 入队前确认资源可见，避免为无权访问的资源创建任务
+    ctx.services.resource.get(resource_id, account_id).await?;
+
+    let payload = JobPayload::ExtractTasks {
+        resource_id,
+        account_id,
+    };
+    let job = ctx.services.job.enqueue(&payload, account_id).await?;
+
+    Ok(Json(ApiResponse::success(job.into())))
+}
@@ -0,0 +1,72 @@
+use salvo::{handler, writing::Json, Depot, Request};
+
+use crate::handler::{DepotExt, RequestExt};
+use crate::model::account::AccountResponse;
+use crate::model::family::{CreateChildRequest, FamilyResponse, UpdateChildRequest};
+use crate::response::{ApiResponse, ApiResult};
+
+#[handler]
+pub async fn get_family(depot: &mut Depot) -> ApiResult<FamilyResponse> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let family = ctx.services.family.get(account_id).await?;
+
+    Ok(Json(ApiResponse::success(family)))
+}
+
+#[handler]
+pub async fn list_children(depot: &mut Depot) -> ApiResult<Vec<AccountResponse>> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let children = ctx.services.family.list_children(account_id).await?;
+    let responses = children
+        .into_iter()
+        .map(AccountResponse::try_from)
+        .collect::<Result<_, _>>()?;
+
+    Ok(Json(ApiResponse::success(responses)))
+}
+
+#[handler]
+pub async fn create_child(req: &mut Request, depot: &mut Depot) -> ApiResult<AccountResponse> {
+    let child_req = req.parse_request_body::<CreateChildRequest>().await?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let child = ctx
+        .services
+        .family
+        .create_child(account_id, child_req)
+        .await?;
+
+    Ok(Json(ApiResponse::success(child.try_into()?)))
+}
+
+#[handler]
+pub async fn update_child(req: &mut Request, depot: &mut Depot) -> ApiResult<()> {
+    let update_req = req.parse_request_body::<UpdateChildRequest>().await?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let child_id = req.require_uuid_param("id")?;
+    ctx.services
+        .family
+        .update_child(account_id, child_id, update_req)
+        .await?;
+
+    Ok(Json(ApiResponse::ok()))
+}
+
+#[handler]
+pub async fn delete_child(req: &mut Request, depot: &mut Depot) -> ApiResult<()> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let child_id = req.require_uuid_param("id")?;
+
+    ctx.services
+        .family
+        .delete_child(account_id, child_id)
+        .await?;
+
+    Ok(Json(ApiResponse::ok()))
+}
@@ -0,0 +1,239 @@
+use salvo::http::header::{self, HeaderValue};
+use salvo::http::StatusCode;
+use salvo::{handler, Depot, Request, Response};
+use serde::Deserialize;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::handler::resource::content_disposition;
+use crate::handler::{DepotExt, RequestExt};
+use crate::model::resource::ResourceResponse;
+use crate::storage::signer::UrlSigner;
+
+// 签名参数
+#[derive(Debug, Deserialize)]
+struct SignedQuery {
+    expires: i64,
+    sig: String,
+}
+
+/// 签名下载链接对应的路径，签发和校验使用同一个 path
+pub(crate) fn resource_file_path(resource_id: uuid::Uuid) -> String {
+    format!("/api/files/{}", resource_id)
+}
+
+pub(crate) fn resource_thumbnail_path(resource_id: uuid::Uuid) -> String {
+    format!("/api/files/{}/thumbnail", resource_id)
+}
+
+/// 为已生成缩略图的资源签发 thumbnail_url，供 <img> 直接加载
+pub(crate) fn sign_thumbnails<'a>(
+    config: &AppConfig,
+    resources: impl IntoIterator<Item = &'a mut ResourceResponse>,
+) {
+    let signer = UrlSigner::from_config(config);
+    for resource in resources {
+        if resource.has_thumbnail {
+            resource.thumbnail_url = Some(signer.sign(&resource_thumbnail_path(resource.id)).url);
+        }
+    }
+}
+
+/// 公开下载：无需登录，校验签名和有效期；支持单区间 Range 请求
+#[handler]
+pub async fn download_file(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let resource_id = req.require_uuid_param("id")?;
+    let query = req
+        .parse_queries::<SignedQuery>()
+        .map_err(|_| AppError::Forbidden)?;
+
+    let config = depot.app_config()?;
+    if !UrlSigner::from_config(config).verify(
+        &resource_file_path(resource_id),
+        query.expires,
+        &query.sig,
+    ) {
+        return Err(AppError::Forbidden);
+    }
+
+    let ctx = depot.app_context()?;
+    let (resource, size) = ctx.services.resource.open_signed(resource_id).await?;
+
+    let range = req
+        .headers()
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok());
+    let range = match parse_range(range, size) {
+        ByteRange::Full => None,
+        ByteRange::Partial(start, end) => Some((start, end)),
+        ByteRange::Unsatisfiable => {
+            res.status_code(StatusCode::RANGE_NOT_SATISFIABLE);
+            res.headers_mut().insert(
+                header::CONTENT_RANGE,
+                header_value(&format!("bytes */{}", size))?,
+            );
+            return Ok(());
+        }
+    };
+
+    let data = ctx.services.resource.read(&resource, range).await?;
+
+    let headers = res.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        header_value(&resource.content_type())?,
+    );
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        content_disposition(&resource.file_name)?,
+    );
+    // 按存储的类型解析，浏览器不再自行猜测
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    if let Some((start, end)) = range {
+        headers.insert(
+            header::CONTENT_RANGE,
+            header_value(&format!("bytes {}-{}/{}", start, end, size))?,
+        );
+        res.status_code(StatusCode::PARTIAL_CONTENT);
+    }
+    res.body(data);
+    Ok(())
+}
+
+/// 公开缩略图：校验签名，缓存时间不超过链接有效期
+#[handler]
+pub async fn download_thumbnail(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let resource_id = req.require_uuid_param("id")?;
+    let query = req
+        .parse_queries::<SignedQuery>()
+        .map_err(|_| AppError::Forbidden)?;
+
+    let config = depot.app_config()?;
+    if !UrlSigner::from_config(config).verify(
+        &resource_thumbnail_path(resource_id),
+        query.expires,
+        &query.sig,
+    ) {
+        return Err(AppError::Forbidden);
+    }
+
+    let ctx = depot.app_context()?;
+    let data = ctx.services.resource.open_thumbnail(resource_id).await?;
+
+    let max_age = (query.expires - chrono::Utc::now().timestamp()).max(0);
+    let headers = res.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("image/jpeg"));
+    headers.insert(
+        header::CACHE_CONTROL,
+        header_value(&format!("private, max-age={}", max_age))?,
+    );
+    res.body(data);
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum ByteRange {
+    Full,
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+// 解析 Range 头，只支持单个区间；格式不合法或多区间时按完整内容返回
+fn parse_range(header: Option<&str>, size: u64) -> ByteRange {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return ByteRange::Full;
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    // bytes=-500：最后 500 字节
+    if start.is_empty() {
+        return match end.parse::<u64>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if size == 0 => ByteRange::Unsatisfiable,
+            Ok(n) => ByteRange::Partial(size.saturating_sub(n), size - 1),
+            Err(_) => ByteRange::Full,
+        };
+    }
+
+    let Ok(start) = start.parse::<u64>() else {
+        return ByteRange::Full;
+    };
+    if start >= size {
+        return ByteRange::Unsatisfiable;
+    }
+    if end.is_empty() {
+        return ByteRange::Partial(start, size - 1);
+    }
+    match end.parse::<u64>() {
+        Ok(end) if end >= start => ByteRange::Partial(start, end.min(size - 1)),
+        _ => ByteRange::Full,
+    }
+}
+
+fn header_value(value: &str) -> Result<HeaderValue, AppError> {
+    HeaderValue::from_str(value).map_err(|_| AppError::Internal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(spec: &str, size: u64) -> ByteRange {
+        parse_range(Some(spec), size)
+    }
+
+    #[test]
+    fn suffix_range_takes_last_bytes() {
+        assert_eq!(range("bytes=-500", 1000), ByteRange::Partial(500, 999));
+        // 后缀长度超过文件大小时返回整个文件
+        assert_eq!(range("bytes=-500", 100), ByteRange::Partial(0, 99));
+        assert_eq!(range("bytes=-0", 1000), ByteRange::Unsatisfiable);
+    }
+
+    #[test]
+    fn start_past_end_is_unsatisfiable() {
+        assert_eq!(range("bytes=1000-", 1000), ByteRange::Unsatisfiable);
+        assert_eq!(range("bytes=2000-3000", 1000), ByteRange::Unsatisfiable);
+    }
+
+    #[test]
+    fn end_is_clamped_to_size() {
+        assert_eq!(range("bytes=0-", 1000), ByteRange::Partial(0, 999));
+        assert_eq!(range("bytes=100-199", 1000), ByteRange::Partial(100, 199));
+        assert_eq!(range("bytes=900-5000", 1000), ByteRange::Partial(900, 999));
+    }
+
+    #[test]
+    fn invalid_or_multiple_ranges_return_full() {
+        assert_eq!(range("bytes=500-100", 1000), ByteRange::Full);
+        assert_eq!(range("bytes=0-99,200-299", 1000), ByteRange::Full);
+        assert_eq!(range("bytes=abc-", 1000), ByteRange::Full);
+        assert_eq!(range("items=0-99", 1000), ByteRange::Full);
+        assert_eq!(parse_range(None, 1000), ByteRange::Full);
+    }
+
+    #[test]
+    fn empty_file_has_no_satisfiable_range() {
+        assert_eq!(range("bytes=0-", 0), ByteRange::Unsatisfiable);
+        assert_eq!(range("bytes=-500", 0), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range(None, 0), ByteRange::Full);
+    }
+}
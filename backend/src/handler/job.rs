@@ -0,0 +1,41 @@
+use salvo::{handler, writing::Json, Depot, Request};
+
+use crate::handler::{DepotExt, RequestExt};
+use crate::model::job::{JobListQuery, JobResponse};
+use crate::response::{ApiResponse, ApiResult};
+
+/// 最近的后台任务，可按状态和类型过滤
+#[handler]
+pub async fn list_jobs(req: &mut Request, depot: &mut Depot) -> ApiResult<Vec<JobResponse>> {
+    let query = req.parse_query_params::<JobListQuery>()?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let jobs = ctx.services.job.list(account_id, &query).await?;
+
+    Ok(Json(ApiResponse::success(
+        jobs.into_iter().map(Into::into).collect(),
+    )))
+}
+
+/// 查询任务状态，客户端提交识别等任务后轮询
+#[handler]
+pub async fn get_job(req: &mut Request, depot: &mut Depot) -> ApiResult<JobResponse> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let job_id = req.require_uuid_param("id")?;
+    let job = ctx.services.job.get(job_id, account_id).await?;
+
+    Ok(Json(ApiResponse::success(job.into())))
+}
+
+/// 重试进入死信的任务
+#[handler]
+pub async fn retry_job(req: &mut Request, depot: &mut Depot) -> ApiResult<JobResponse> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let job_id = req.require_uuid_param("id")?;
+    let job = ctx.services.job.retry(job_id, account_id).await?;
+
+    Ok(Json(ApiResponse::success(job.into())))
+}
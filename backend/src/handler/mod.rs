@@ -1,7 +1,8 @@
 use crate::app::context::AppContext;
 use crate::config::AppConfig;
 use crate::error::AppError;
-use crate::model::account::Claims;
+use crate::middleware::permission::Permission;
+use crate::model::account::{Claims, Role};
 use salvo::{prelude::JwtAuthDepotExt, Depot, Request};
 use serde::de::DeserializeOwned;
 use sqlx::PgPool;
@@ -9,9 +10,17 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 pub mod account;
+pub mod extraction;
+pub mod family;
+pub mod file;
 pub mod health;
+pub mod job;
 pub mod register;
+pub mod resource;
 pub mod task;
+pub mod task_draft;
+pub mod task_link;
+pub mod task_resource;
 
 /// Handler 层内部工具：安全获取 depot 中的依赖
 pub(crate) fn require_state<T: Send + Sync + 'static>(depot: &Depot) -> Result<&T, AppError> {
@@ -26,7 +35,11 @@ pub(crate) fn require_state<T: Send + Sync + 'static>(depot: &Depot) -> Result<&
 pub(crate) trait DepotExt {
     fn pool(&self) -> Result<&PgPool, AppError>;
     fn app_context(&self) -> Result<&Arc<AppContext>, AppError>;
+    fn current_claims(&self) -> Result<&Claims, AppError>;
     fn current_account_id(&self) -> Result<Uuid, AppError>;
+    fn current_session_id(&self) -> Result<Uuid, AppError>;
+    fn current_role(&self) -> Result<Role, AppError>;
+    fn require_permission(&self, permission: Permission) -> Result<(), AppError>;
     fn app_config(&self) -> Result<&AppConfig, AppError>;
 }
 
@@ -39,17 +52,41 @@ impl DepotExt for Depot {
         require_state::<Arc<AppContext>>(self)
     }
 
+    fn current_claims(&self) -> Result<&Claims, AppError> {
+        self.jwt_auth_data::<Claims>()
+            .map(|data| &data.claims)
+            .ok_or(AppError::Unauthorized)
+    }
+
     // 从 JWT 中获取 ID
     fn current_account_id(&self) -> Result<Uuid, AppError> {
-        let claims = &self
-            .jwt_auth_data::<Claims>()
-            .ok_or(AppError::Unauthorized)?;
+        let claims = self.current_claims()?;
 
-        let account_id = Uuid::parse_str(&claims.claims.sub).map_err(|_| AppError::Unauthorized)?;
+        let account_id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::Unauthorized)?;
 
         Ok(account_id)
     }
 
+    // 从 JWT 中获取会话 ID
+    fn current_session_id(&self) -> Result<Uuid, AppError> {
+        let claims = self.current_claims()?;
+
+        Uuid::parse_str(&claims.sid).map_err(|_| AppError::Unauthorized)
+    }
+
+    fn current_role(&self) -> Result<Role, AppError> {
+        Ok(self.current_claims()?.role)
+    }
+
+    // 按角色校验权限点，无权限返回 403
+    fn require_permission(&self, permission: Permission) -> Result<(), AppError> {
+        if permission.allowed_for(self.current_role()?) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden)
+        }
+    }
+
     fn app_config(&self) -> Result<&AppConfig, AppError> {
         require_state::<AppConfig>(self)
     }
@@ -57,6 +94,7 @@ impl DepotExt for Depot {
 
 pub(crate) trait RequestExt {
     fn require_uuid_param(&self, name: &str) -> Result<Uuid, AppError>;
+    fn parse_query_params<T: DeserializeOwned>(&mut self) -> Result<T, AppError>;
     async fn parse_request_body<T: DeserializeOwned>(&mut self) -> Result<T, AppError>;
 }
 
@@ -65,12 +103,29 @@ impl RequestExt for Request {
         let value = &self
             .param::<String>(name)
             .ok_or_else(|| AppError::BadRequest(format!("{} 缺少参数", name)))?;
-        Uuid::parse_str(&value).map_err(|_| AppError::BadRequest(format!("无效的 UUID: {}", name)))
+        Uuid::parse_str(value).map_err(|_| AppError::BadRequest(format!("无效的 UUID: {}", name)))
     }
 
+    fn parse_query_params<T: DeserializeOwned>(&mut self) -> Result<T, AppError> {
+        self.parse_queries::<T>()
+            .map_err(|e| AppError::BadRequest(format!("查询参数错误: {}", e)))
+    }
+
+    // 解析 JSON 请求体，错误信息带上出错字段（如枚举取值不合法）
     async fn parse_request_body<T: DeserializeOwned>(&mut self) -> Result<T, AppError> {
-        self.parse_json::<T>()
+        let body = self
+            .payload()
             .await
-            .map_err(|e| AppError::BadRequest(e.to_string()))
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+        let deserializer = &mut serde_json::Deserializer::from_slice(body);
+        serde_path_to_error::deserialize(deserializer).map_err(|e| {
+            let path = e.path().to_string();
+            if path == "." {
+                AppError::BadRequest(e.inner().to_string())
+            } else {
+                AppError::BadRequest(format!("字段 {} 无效: {}", path, e.inner()))
+            }
+        })
     }
 }
@@ -1,10 +1,7 @@
-use crate::error::AppError;
 use crate::handler::{DepotExt, RequestExt};
-use crate::model::account::{AccountCreate, AccountResponse, RegisterRequest};
+use crate::model::account::{AccountCreate, AccountResponse, RegisterRequest, Role};
 use crate::response::{ApiResponse, ApiResult};
-use argon2::password_hash::rand_core::OsRng;
-use argon2::password_hash::SaltString;
-use argon2::{Argon2, PasswordHasher};
+use crate::service::account::hash_password;
 use salvo::writing::Json;
 use salvo::{handler, Depot, Request};
 
@@ -14,39 +11,37 @@ pub async fn register(req: &mut Request, depot: &mut Depot) -> ApiResult<Account
     let reg_req = req.parse_request_body::<RegisterRequest>().await?;
 
     // 2. 加密
-    let salt = SaltString::generate(&mut OsRng);
-    let password_hash = Argon2::default()
-        .hash_password(reg_req.password.as_bytes(), &salt)
-        .map_err(|_| AppError::Internal)?
-        .to_string();
+    let password_hash = hash_password(&reg_req.password)?;
 
-    // 先写死
-    // todo 写死的 role
-    let role = "parent".to_string();
+    // 公开注册只能注册家长，孩子账户由家长在家庭中创建
+    let role = Role::Parent;
     let uid = uuid::Uuid::new_v4();
     let now = chrono::Utc::now();
 
-    // 3. 写入数据
+    // 3. 写入数据（家长注册时同时创建家庭，家庭 ID 复用账户 ID）
     let account_create = AccountCreate {
         id: uid,
         username: Some(reg_req.username.clone()),
         nickname: reg_req.nickname.clone(),
         password_hash: Some(password_hash),
-        role,
+        role: role.as_str().to_string(),
         created_at: now,
         updated_at: now,
         created_by: uid,
         updated_by: uid,
         is_deleted: false,
+        family_id: uid,
     };
     let ctx = depot.app_context()?;
-    ctx.services.account.create(account_create).await?;
+    ctx.services.account.register_parent(account_create).await?;
 
     // 4. 返回响应
     let response = AccountResponse {
         id: uid,
         username: Some(reg_req.username.clone()),
         nickname: reg_req.nickname.clone(),
+        role,
+        family_id: uid,
     };
 
     Ok(Json(ApiResponse::success(response)))
@@ -0,0 +1,240 @@
+use salvo::http::header::{self, HeaderValue};
+use salvo::{handler, writing::Json, Depot, Request, Response};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::handler::file::{resource_file_path, sign_thumbnails};
+use crate::handler::{DepotExt, RequestExt};
+use crate::model::resource::{
+    ResourceDetailResponse, ResourceListQuery, ResourcePage, ResourceResponse, StorageUsageQuery,
+    StorageUsageResponse, UploadFile,
+};
+use crate::response::{ApiResponse, ApiResult};
+use crate::storage::signer::{SignedUrl, UrlSigner};
+
+/// 上传资源：multipart/form-data，字段 file 为文件，account_id 可选（家长为孩子上传）
+#[handler]
+pub async fn upload_resource(req: &mut Request, depot: &mut Depot) -> ApiResult<ResourceResponse> {
+    let owner_id = match req.form::<String>("account_id").await {
+        Some(raw) => Some(
+            Uuid::parse_str(&raw)
+                .map_err(|_| AppError::BadRequest("无效的 UUID: account_id".into()))?,
+        ),
+        None => None,
+    };
+    let config = depot.app_config()?;
+    let file = read_upload(req, "file", config.upload.max_file_size()).await?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let resource = ctx
+        .services
+        .resource
+        .upload(config, account_id, owner_id, file)
+        .await?;
+
+    Ok(Json(ApiResponse::success(resource.into())))
+}
+
+#[handler]
+pub async fn list_resources(req: &mut Request, depot: &mut Depot) -> ApiResult<ResourcePage> {
+    let query = req.parse_query_params::<ResourceListQuery>()?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let (resources, next_cursor) = ctx.services.resource.list(account_id, &query).await?;
+    let mut page = ResourcePage {
+        items: resources.into_iter().map(Into::into).collect(),
+        next_cursor,
+    };
+    sign_thumbnails(depot.app_config()?, &mut page.items);
+    Ok(Json(ApiResponse::success(page)))
+}
+
+#[handler]
+pub async fn storage_usage(
+    req: &mut Request,
+    depot: &mut Depot,
+) -> ApiResult<StorageUsageResponse> {
+    let query = req.parse_query_params::<StorageUsageQuery>()?;
+
+    let ctx = depot.app_context()?;
+    let config = depot.app_config()?;
+    let account_id = depot.current_account_id()?;
+    let usage = ctx
+        .services
+        .resource
+        .usage(config, account_id, query.account_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(usage)))
+}
+
+#[handler]
+pub async fn get_resource(
+    req: &mut Request,
+    depot: &mut Depot,
+) -> ApiResult<ResourceDetailResponse> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let resource_id = req.require_uuid_param("id")?;
+
+    let resource = ctx.services.resource.get(resource_id, account_id).await?;
+    let mut detail: ResourceDetailResponse = resource.into();
+    sign_thumbnails(depot.app_config()?, [&mut detail.resource]);
+
+    Ok(Json(ApiResponse::success(detail)))
+}
+
+#[handler]
+pub async fn download_resource(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let resource_id = req.require_uuid_param("id")?;
+
+    let (resource, data) = ctx
+        .services
+        .resource
+        .download(resource_id, account_id)
+        .await?;
+
+    let content_type =
+        HeaderValue::from_str(&resource.content_type()).map_err(|_| AppError::Internal)?;
+    res.headers_mut().insert(header::CONTENT_TYPE, content_type);
+    res.headers_mut().insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    res.headers_mut().insert(
+        header::CONTENT_DISPOSITION,
+        content_disposition(&resource.file_name)?,
+    );
+    res.body(data);
+    Ok(())
+}
+
+/// 签发限时下载链接：GET /api/files/{id}?expires=...&sig=...，无需登录即可下载
+#[handler]
+pub async fn create_download_url(req: &mut Request, depot: &mut Depot) -> ApiResult<SignedUrl> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let resource_id = req.require_uuid_param("id")?;
+
+    let resource = ctx.services.resource.get(resource_id, account_id).await?;
+    let signer = UrlSigner::from_config(depot.app_config()?);
+
+    Ok(Json(ApiResponse::success(
+        signer.sign(&resource_file_path(resource.id)),
+    )))
+}
+
+#[handler]
+pub async fn delete_resource(req: &mut Request, depot: &mut Depot) -> ApiResult<()> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let resource_id = req.require_uuid_param("id")?;
+
+    ctx.services
+        .resource
+        .delete(resource_id, account_id)
+        .await?;
+
+    Ok(Json(ApiResponse::ok()))
+}
+
+// 读取 multipart 中的文件字段
+async fn read_upload(
+    req: &mut Request,
+    field: &str,
+    max_size: usize,
+) -> Result<UploadFile, AppError> {
+    let part = req
+        .file(field)
+        .await
+        .ok_or_else(|| AppError::BadRequest(format!("缺少上传文件字段 {}", field)))?;
+    // 分块传输没有 Content-Length，body_limit 拦不住，读入内存前再校验一次
+    if part.size() > max_size as u64 {
+        return Err(AppError::PayloadTooLarge(max_size));
+    }
+
+    let data = tokio::fs::read(part.path()).await.map_err(|e| {
+        tracing::error!(err = ?e, "读取上传临时文件失败");
+        AppError::Internal
+    })?;
+
+    Ok(UploadFile {
+        file_name: part.name().unwrap_or_default().to_string(),
+        data,
+    })
+}
+
+/// 下载文件名：ASCII 回退 + RFC 5987 编码的 UTF-8 文件名（兼容中文）
+pub(crate) fn content_disposition(file_name: &str) -> Result<HeaderValue, AppError> {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| {
+            if (c.is_ascii_graphic() && c != '"' && c != '\\') || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    let mut encoded = String::new();
+    for b in file_name.bytes() {
+        if b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b) {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", b));
+        }
+    }
+
+    HeaderValue::from_str(&format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback, encoded
+    ))
+    .map_err(|_| AppError::Internal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disposition(file_name: &str) -> String {
+        content_disposition(file_name)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn ascii_name_is_kept() {
+        assert_eq!(
+            disposition("homework 1.pdf"),
+            "attachment; filename=\"homework 1.pdf\"; filename*=UTF-8''homework%201.pdf"
+        );
+    }
+
+    #[test]
+    fn utf8_name_is_percent_encoded() {
+        assert_eq!(
+            disposition("数学作业.pdf"),
+            "attachment; filename=\"____.pdf\"; \
+             filename*=UTF-8''%E6%95%B0%E5%AD%A6%E4%BD%9C%E4%B8%9A.pdf"
+        );
+    }
+
+    #[test]
+    fn quotes_and_control_characters_are_escaped() {
+        assert_eq!(
+            disposition("a\"b\\c\r\n.txt"),
+            "attachment; filename=\"a_b_c__.txt\"; filename*=UTF-8''a%22b%5Cc%0D%0A.txt"
+        );
+    }
+}
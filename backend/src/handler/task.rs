@@ -1,7 +1,12 @@
 use salvo::{handler, writing::Json, Depot, Request};
 
+use crate::handler::file::sign_thumbnails;
 use crate::handler::{DepotExt, RequestExt};
-use crate::model::task::{CreateTaskRequest, UpdateTaskRequest};
+use crate::middleware::permission::Permission;
+use crate::model::task::{
+    CompleteTaskQuery, CreateTaskRequest, PatchTaskRequest, TaskDetailQuery, TaskListQuery,
+    TaskOptionsResponse, TaskPage, TaskTransition, UpdateTaskRequest,
+};
 use crate::{
     model::task::TaskResponse,
     response::{ApiResponse, ApiResult},
@@ -16,27 +21,64 @@ pub async fn create_task(req: &mut Request, depot: &mut Depot) -> ApiResult<Task
     let account_id = depot.current_account_id()?;
     let task = ctx.services.task.create(account_id, &task_req).await?;
 
-    Ok(Json(ApiResponse::success(task.into())))
+    Ok(Json(ApiResponse::success(
+        ctx.services.task.to_response(task).await?,
+    )))
 }
 
 #[handler]
-pub async fn list_tasks(depot: &mut Depot) -> ApiResult<Vec<TaskResponse>> {
+pub async fn list_tasks(req: &mut Request, depot: &mut Depot) -> ApiResult<TaskPage> {
+    let query = req.parse_query_params::<TaskListQuery>()?;
+
     let ctx = depot.app_context()?;
     let account_id = depot.current_account_id()?;
-    let tasks = ctx.services.task.list(account_id).await?;
-    let responses = tasks.into_iter().map(Into::into).collect();
-    Ok(Json(ApiResponse::success(responses)))
+    let (tasks, next_cursor) = ctx.services.task.list(account_id, &query).await?;
+    let page = TaskPage {
+        items: ctx.services.task.to_responses(tasks).await?,
+        next_cursor,
+    };
+    Ok(Json(ApiResponse::success(page)))
+}
+
+#[handler]
+pub async fn task_options() -> ApiResult<TaskOptionsResponse> {
+    Ok(Json(ApiResponse::success(TaskOptionsResponse::default())))
 }
 
 #[handler]
 pub async fn get_task(req: &mut Request, depot: &mut Depot) -> ApiResult<TaskResponse> {
+    let query = req.parse_query_params::<TaskDetailQuery>()?;
+
     let ctx = depot.app_context()?;
     let account_id = depot.current_account_id()?;
     let task_id = req.require_uuid_param("id")?;
 
     let task = ctx.services.task.get(task_id, account_id).await?;
 
-    Ok(Json(ApiResponse::success(task.into())))
+    // ?expand=tree：展开计划 / 拆分出的子任务
+    let children = if query.expands("tree") {
+        Some(ctx.services.task_link.tree(&task, account_id).await?)
+    } else {
+        None
+    };
+    let resources = if query.expands("resources") {
+        Some(
+            ctx.services
+                .task_resource
+                .summaries(&task, account_id)
+                .await?,
+        )
+    } else {
+        None
+    };
+    let mut response = ctx.services.task.to_response(task).await?;
+    response.children = children;
+    response.resources = resources;
+    if let Some(resources) = response.resources.as_mut() {
+        sign_thumbnails(depot.app_config()?, resources);
+    }
+
+    Ok(Json(ApiResponse::success(response)))
 }
 
 #[handler]
@@ -54,6 +96,24 @@ pub async fn update_task(req: &mut Request, depot: &mut Depot) -> ApiResult<()>
     Ok(Json(ApiResponse::ok()))
 }
 
+#[handler]
+pub async fn patch_task(req: &mut Request, depot: &mut Depot) -> ApiResult<TaskResponse> {
+    let patch_req = req.parse_request_body::<PatchTaskRequest>().await?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let task_id = req.require_uuid_param("id")?;
+    let task = ctx
+        .services
+        .task
+        .patch(task_id, account_id, patch_req)
+        .await?;
+
+    Ok(Json(ApiResponse::success(
+        ctx.services.task.to_response(task).await?,
+    )))
+}
+
 #[handler]
 pub async fn delete_task(req: &mut Request, depot: &mut Depot) -> ApiResult<()> {
     let ctx = depot.app_context()?;
@@ -64,3 +124,49 @@ pub async fn delete_task(req: &mut Request, depot: &mut Depot) -> ApiResult<()>
 
     Ok(Json(ApiResponse::ok()))
 }
+
+#[handler]
+pub async fn complete_task(req: &mut Request, depot: &mut Depot) -> ApiResult<TaskResponse> {
+    let query = req.parse_query_params::<CompleteTaskQuery>()?;
+    // 忽略前置任务强制完成，仅限有编辑权限的家长
+    if query.force {
+        depot.require_permission(Permission::TaskWrite)?;
+    }
+    transition_task(req, depot, TaskTransition::Complete, query.force).await
+}
+
+#[handler]
+pub async fn reopen_task(req: &mut Request, depot: &mut Depot) -> ApiResult<TaskResponse> {
+    transition_task(req, depot, TaskTransition::Reopen, false).await
+}
+
+#[handler]
+pub async fn archive_task(req: &mut Request, depot: &mut Depot) -> ApiResult<TaskResponse> {
+    transition_task(req, depot, TaskTransition::Archive, false).await
+}
+
+#[handler]
+pub async fn unarchive_task(req: &mut Request, depot: &mut Depot) -> ApiResult<TaskResponse> {
+    transition_task(req, depot, TaskTransition::Unarchive, false).await
+}
+
+async fn transition_task(
+    req: &mut Request,
+    depot: &mut Depot,
+    transition: TaskTransition,
+    force: bool,
+) -> ApiResult<TaskResponse> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let task_id = req.require_uuid_param("id")?;
+
+    let task = ctx
+        .services
+        .task
+        .transition(task_id, account_id, transition, force)
+        .await?;
+
+    Ok(Json(ApiResponse::success(
+        ctx.services.task.to_response(task).await?,
+    )))
+}
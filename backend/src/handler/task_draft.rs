@@ -0,0 +1,73 @@
+use salvo::{handler, writing::Json, Depot, Request};
+
+use crate::handler::{DepotExt, RequestExt};
+use crate::model::task::TaskResponse;
+use crate::model::task_draft::{
+    PatchTaskDraftRequest, TaskDraftIdsRequest, TaskDraftListQuery, TaskDraftResponse,
+};
+use crate::response::{ApiResponse, ApiResult};
+
+/// 草稿列表，默认只返回待确认的
+#[handler]
+pub async fn list_drafts(
+    req: &mut Request,
+    depot: &mut Depot,
+) -> ApiResult<Vec<TaskDraftResponse>> {
+    let query = req.parse_query_params::<TaskDraftListQuery>()?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let drafts = ctx.services.task_draft.list(account_id, &query).await?;
+
+    Ok(Json(ApiResponse::success(
+        drafts.into_iter().map(Into::into).collect(),
+    )))
+}
+
+#[handler]
+pub async fn patch_draft(req: &mut Request, depot: &mut Depot) -> ApiResult<TaskDraftResponse> {
+    let patch_req = req.parse_request_body::<PatchTaskDraftRequest>().await?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let draft_id = req.require_uuid_param("id")?;
+    let draft = ctx
+        .services
+        .task_draft
+        .patch(draft_id, account_id, patch_req)
+        .await?;
+
+    Ok(Json(ApiResponse::success(draft.into())))
+}
+
+/// 确认草稿，返回生成的任务
+#[handler]
+pub async fn accept_drafts(req: &mut Request, depot: &mut Depot) -> ApiResult<Vec<TaskResponse>> {
+    let ids_req = req.parse_request_body::<TaskDraftIdsRequest>().await?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let tasks = ctx
+        .services
+        .task_draft
+        .accept(account_id, &ids_req.draft_ids)
+        .await?;
+
+    Ok(Json(ApiResponse::success(
+        ctx.services.task.to_responses(tasks).await?,
+    )))
+}
+
+#[handler]
+pub async fn reject_drafts(req: &mut Request, depot: &mut Depot) -> ApiResult<()> {
+    let ids_req = req.parse_request_body::<TaskDraftIdsRequest>().await?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    ctx.services
+        .task_draft
+        .reject(account_id, &ids_req.draft_ids)
+        .await?;
+
+    Ok(Json(ApiResponse::ok()))
+}
@@ -0,0 +1,75 @@
+use salvo::{handler, writing::Json, Depot, Request};
+
+use crate::handler::{DepotExt, RequestExt};
+use crate::model::task_link::{CreateTaskLinkRequest, LinkedTaskResponse, TaskLinkQuery};
+use crate::response::{ApiResponse, ApiResult};
+
+#[handler]
+pub async fn list_children(
+    req: &mut Request,
+    depot: &mut Depot,
+) -> ApiResult<Vec<LinkedTaskResponse>> {
+    let query = req.parse_query_params::<TaskLinkQuery>()?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let task_id = req.require_uuid_param("id")?;
+    let children = ctx
+        .services
+        .task_link
+        .children(task_id, account_id, query.link_type)
+        .await?;
+
+    Ok(Json(ApiResponse::success(children)))
+}
+
+#[handler]
+pub async fn list_parents(
+    req: &mut Request,
+    depot: &mut Depot,
+) -> ApiResult<Vec<LinkedTaskResponse>> {
+    let query = req.parse_query_params::<TaskLinkQuery>()?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let task_id = req.require_uuid_param("id")?;
+    let parents = ctx
+        .services
+        .task_link
+        .parents(task_id, account_id, query.link_type)
+        .await?;
+
+    Ok(Json(ApiResponse::success(parents)))
+}
+
+#[handler]
+pub async fn create_link(req: &mut Request, depot: &mut Depot) -> ApiResult<LinkedTaskResponse> {
+    let link_req = req.parse_request_body::<CreateTaskLinkRequest>().await?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let task_id = req.require_uuid_param("id")?;
+    let linked = ctx
+        .services
+        .task_link
+        .attach(task_id, account_id, &link_req)
+        .await?;
+
+    Ok(Json(ApiResponse::success(linked)))
+}
+
+#[handler]
+pub async fn delete_link(req: &mut Request, depot: &mut Depot) -> ApiResult<()> {
+    let query = req.parse_query_params::<TaskLinkQuery>()?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let task_id = req.require_uuid_param("id")?;
+    let child_id = req.require_uuid_param("child_id")?;
+    ctx.services
+        .task_link
+        .detach(task_id, child_id, account_id, query.link_type)
+        .await?;
+
+    Ok(Json(ApiResponse::ok()))
+}
@@ -0,0 +1,83 @@
+use salvo::{handler, writing::Json, Depot, Request};
+
+use crate::handler::file::sign_thumbnails;
+use crate::handler::{DepotExt, RequestExt};
+use crate::model::task_resource::{
+    AttachResourceRequest, LinkedResourceResponse, ResourceTaskResponse,
+};
+use crate::response::{ApiResponse, ApiResult};
+
+#[handler]
+pub async fn list_task_resources(
+    req: &mut Request,
+    depot: &mut Depot,
+) -> ApiResult<Vec<LinkedResourceResponse>> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let task_id = req.require_uuid_param("id")?;
+
+    let mut resources = ctx
+        .services
+        .task_resource
+        .resources(task_id, account_id)
+        .await?;
+    sign_thumbnails(
+        depot.app_config()?,
+        resources.iter_mut().map(|l| &mut l.resource),
+    );
+
+    Ok(Json(ApiResponse::success(resources)))
+}
+
+#[handler]
+pub async fn attach_resource(
+    req: &mut Request,
+    depot: &mut Depot,
+) -> ApiResult<LinkedResourceResponse> {
+    let attach_req = req.parse_request_body::<AttachResourceRequest>().await?;
+
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let task_id = req.require_uuid_param("id")?;
+    let mut linked = ctx
+        .services
+        .task_resource
+        .attach(task_id, attach_req.resource_id, account_id)
+        .await?;
+    sign_thumbnails(depot.app_config()?, [&mut linked.resource]);
+
+    Ok(Json(ApiResponse::success(linked)))
+}
+
+#[handler]
+pub async fn detach_resource(req: &mut Request, depot: &mut Depot) -> ApiResult<()> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let task_id = req.require_uuid_param("id")?;
+    let resource_id = req.require_uuid_param("resource_id")?;
+
+    ctx.services
+        .task_resource
+        .detach(task_id, resource_id, account_id)
+        .await?;
+
+    Ok(Json(ApiResponse::ok()))
+}
+
+#[handler]
+pub async fn list_resource_tasks(
+    req: &mut Request,
+    depot: &mut Depot,
+) -> ApiResult<Vec<ResourceTaskResponse>> {
+    let ctx = depot.app_context()?;
+    let account_id = depot.current_account_id()?;
+    let resource_id = req.require_uuid_param("id")?;
+
+    let tasks = ctx
+        .services
+        .task_resource
+        .tasks(resource_id, account_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(tasks)))
+}
@@ -0,0 +1,53 @@
+use crate::error::AppError;
+use crate::llm::{LlmPrompt, LlmProvider};
+use salvo::async_trait;
+
+// PRD 8.2 中的示例输出
+const DEFAULT_RESPONSE: &str = r#"{
+  "tasks": [
+    {
+      "title": "数学作业",
+      "type": "homework",
+      "subject": "math",
+      "due_date": null,
+      "items": ["第1题", "第2题", "第3题"]
+    },
+    {
+      "title": "预习英语第三单元",
+      "type": "preview",
+      "subject": "english",
+      "due_date": null,
+      "items": []
+    }
+  ]
+}"#;
+
+/// 固定输出的 provider，不访问网络，结果与输入无关
+pub struct MockProvider {
+    response: String,
+}
+
+impl MockProvider {
+    /// 指定输出内容，用于测试解析和校验逻辑
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn new(response: impl Into<String>) -> Self {
+        Self {
+            response: response.into(),
+        }
+    }
+}
+
+impl Default for MockProvider {
+    fn default() -> Self {
+        Self {
+            response: DEFAULT_RESPONSE.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for MockProvider {
+    async fn complete(&self, _prompt: &LlmPrompt) -> Result<String, AppError> {
+        Ok(self.response.clone())
+    }
+}
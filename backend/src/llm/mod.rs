@@ -0,0 +1,36 @@
+use crate::config::{AppConfig, LlmBackend};
+use crate::error::AppError;
+use salvo::async_trait;
+use std::sync::Arc;
+
+pub mod mock;
+pub mod openai;
+
+/// 多模态输入中的图片
+pub struct LlmImage {
+    pub mime: String,
+    pub data: Vec<u8>,
+}
+
+/// 一次对话请求：system 提示词 + 用户输入的文字和图片
+pub struct LlmPrompt {
+    pub system: String,
+    pub text: String,
+    pub images: Vec<LlmImage>,
+}
+
+/// 大模型调用，返回模型输出的原始文本，由调用方解析
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    async fn complete(&self, prompt: &LlmPrompt) -> Result<String, AppError>;
+}
+
+/// 按配置创建 LLM provider，未启用时返回 None
+pub fn create_llm_provider(config: &AppConfig) -> Result<Option<Arc<dyn LlmProvider>>, AppError> {
+    let provider: Arc<dyn LlmProvider> = match config.llm.backend {
+        LlmBackend::Disabled => return Ok(None),
+        LlmBackend::Mock => Arc::new(mock::MockProvider::default()),
+        LlmBackend::OpenAiCompatible => Arc::new(openai::OpenAiProvider::new(&config.llm)?),
+    };
+    Ok(Some(provider))
+}
@@ -0,0 +1,106 @@
+use crate::config::LlmConfig;
+use crate::error::AppError;
+use crate::llm::{LlmPrompt, LlmProvider};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use salvo::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+/// OpenAI 兼容的 chat/completions 接口（GLM / DeepSeek / Qwen / Doubao 等），
+/// 图片以 data URL 放在 image_url 中
+pub struct OpenAiProvider {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: String,
+    model: String,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: Option<String>,
+}
+
+impl OpenAiProvider {
+    pub fn new(config: &LlmConfig) -> Result<Self, AppError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| AppError::ConfigParse(format!("创建 LLM HTTP 客户端失败: {}", e)))?;
+
+        Ok(Self {
+            client,
+            endpoint: format!("{}/chat/completions", config.base_url),
+            api_key: config.api_key.clone(),
+            model: config.model.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiProvider {
+    async fn complete(&self, prompt: &LlmPrompt) -> Result<String, AppError> {
+        let mut content = vec![json!({ "type": "text", "text": prompt.text })];
+        for image in &prompt.images {
+            let url = format!(
+                "data:{};base64,{}",
+                image.mime,
+                STANDARD.encode(&image.data)
+            );
+            content.push(json!({ "type": "image_url", "image_url": { "url": url } }));
+        }
+        let body = json!({
+            "model": self.model,
+            "temperature": 0.1,
+            "messages": [
+                { "role": "system", "content": prompt.system },
+                { "role": "user", "content": content },
+            ],
+        });
+
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::error!(err = ?e, endpoint = %self.endpoint, "调用 LLM 失败");
+                AppError::Upstream("AI 服务连接失败".into())
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let detail = response.text().await.unwrap_or_default();
+            tracing::error!(%status, detail, "LLM 返回错误");
+            return Err(AppError::Upstream(format!(
+                "AI 服务返回 {}",
+                status.as_u16()
+            )));
+        }
+
+        let parsed: ChatResponse = response.json().await.map_err(|e| {
+            tracing::error!(err = ?e, "解析 LLM 响应失败");
+            AppError::Upstream("AI 服务响应格式错误".into())
+        })?;
+
+        parsed
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|c| c.message.content)
+            .ok_or_else(|| AppError::Upstream("AI 服务未返回内容".into()))
+    }
+}
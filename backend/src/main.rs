@@ -1,36 +1,139 @@
+use crate::app::cli::{parse_args, Command, USAGE};
 use crate::app::context::AppContext;
-use crate::config::AppConfig;
+use crate::app::shutdown::wait_for_signal;
+use crate::config::{load_env_files, AppConfig, LogFormat, MigrateConfig};
 use crate::db::create_pool;
-use crate::handler::task::{create_task, delete_task, get_task, list_tasks, update_task};
-use crate::handler::{account::*, health::*, register::*};
-use crate::middleware::auth::create_jwt_auth;
+use crate::handler::extraction::extract_tasks;
+use crate::handler::file::{download_file, download_thumbnail};
+use crate::handler::job::{get_job, list_jobs, retry_job};
+use crate::handler::resource::{
+    create_download_url, delete_resource, download_resource, get_resource, list_resources,
+    storage_usage, upload_resource,
+};
+use crate::handler::task::{
+    archive_task, complete_task, create_task, delete_task, get_task, list_tasks, patch_task,
+    reopen_task, task_options, unarchive_task, update_task,
+};
+use crate::handler::task_draft::{accept_drafts, list_drafts, patch_draft, reject_drafts};
+use crate::handler::task_link::{
+    create_link, delete_link, list_children as list_task_children, list_parents,
+};
+use crate::handler::task_resource::{
+    attach_resource, detach_resource, list_resource_tasks, list_task_resources,
+};
+use crate::handler::{account::*, family::*, health::*, register::*};
+use crate::llm::create_llm_provider;
+use crate::middleware::auth::{create_jwt_auth, session_guard};
+use crate::middleware::body_limit::body_limit;
+use crate::middleware::cors::create_cors;
+use crate::middleware::permission::{require, Permission};
+use crate::ocr::create_ocr_engine;
+use crate::storage::create_storage;
+use crate::worker::spawn_workers;
 use salvo::prelude::*;
+use salvo::timeout::Timeout;
 use std::error::Error;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
 
 mod app;
 mod config;
 mod db;
 mod error;
 mod handler;
+mod llm;
 mod middleware;
+mod migrate;
 mod model;
+mod ocr;
 mod repository;
 mod response;
 mod service;
+mod storage;
+#[cfg(test)]
+mod test_support;
+mod worker;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    // 加载环境变量
-    dotenvy::dotenv()?;
-    let config = AppConfig::from_env()?;
+    // 子命令：serve（默认）/ --check-config / migrate up|status|down-to
+    let command = match parse_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if command == Command::Help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    // 加载配置文件（均为可选，容器部署时直接注入环境变量）
+    let loaded_files = load_env_files()?;
+    // migrate 只校验数据库配置，服务配置（JWT、AI 服务等）不完整时也能执行迁移
+    if let Command::Migrate(migrate_command) = command {
+        let config = MigrateConfig::from_env().unwrap_or_else(|report| {
+            eprint!("{}", report);
+            std::process::exit(1);
+        });
+        tracing_subscriber::fmt()
+            .with_env_filter(
+                tracing_subscriber::EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| "info".into()),
+            )
+            .init();
+        let pgpool = create_pool(&config.db).await?;
+        let result = migrate::run_command(&pgpool, config.rust_env, migrate_command).await;
+        pgpool.close().await;
+        return result;
+    }
+    let (config, entries) = match AppConfig::from_env() {
+        Ok(loaded) => loaded,
+        Err(report) => {
+            eprint!("{}", report);
+            std::process::exit(1);
+        }
+    };
+    // --check-config：校验配置并输出生效的配置（敏感值已脱敏）后退出
+    if command == Command::CheckConfig {
+        println!("# 配置文件: {:?}", loaded_files);
+        for entry in &entries {
+            println!("{}", entry);
+        }
+        return Ok(());
+    }
 
     // 初始化日志
-    tracing_subscriber::fmt::init();
+    let log_filter = tracing_subscriber::EnvFilter::try_new(&config.log_filter)?;
+    let subscriber = tracing_subscriber::fmt().with_env_filter(log_filter);
+    match config.log_format {
+        LogFormat::Json => subscriber.json().init(),
+        LogFormat::Pretty => subscriber.pretty().init(),
+    }
+    tracing::info!(env = config.rust_env.as_str(), files = ?loaded_files, "配置已加载");
 
     // 数据库 pool
-    let pgpool = create_pool(&config).await?;
-    let ctx = Arc::new(AppContext::new(pgpool));
+    let pgpool = create_pool(&config.db).await?;
+    // 启动前迁移到最新结构；多个实例同时启动时由迁移锁串行执行
+    if config.auto_migrate {
+        let applied = migrate::run_pending(&pgpool).await?;
+        tracing::info!(?applied, "自动迁移完成");
+    }
+    // 文件存储
+    let storage = create_storage(&config).await?;
+    // AI 服务（可选）
+    let llm = create_llm_provider(&config)?;
+    // OCR 文字预提取（可选）
+    let ocr = create_ocr_engine(&config);
+    let ctx = Arc::new(AppContext::new(pgpool, storage, llm, ocr));
+
+    // 后台任务 worker，与 HTTP 服务共用 AppContext
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let mut workers = spawn_workers(ctx.clone(), &config, shutdown_rx);
+    let pool = ctx.pool.clone();
 
     // 创建中间件
     let auth_middleware = create_jwt_auth(&config.jwt_secret);
@@ -39,25 +142,194 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let router: Router = Router::new()
         .hoop(affix_state::inject(ctx))
         .hoop(affix_state::inject(config.clone()))
+        .hoop(body_limit(config.request_body_limit))
+        .hoop(Timeout::new(Duration::from_secs(
+            config.request_timeout_secs,
+        )))
         .push(Router::with_path("health").get(health_check))
         .push(Router::with_path("api/account/register").post(register))
         .push(Router::with_path("api/account/login").post(login))
+        .push(Router::with_path("api/account/refresh").post(refresh))
+        // 签名下载链接，凭签名访问，不经过 JWT 认证
+        .push(
+            Router::with_path("api/files/{id}")
+                .get(download_file)
+                .push(Router::with_path("thumbnail").get(download_thumbnail)),
+        )
         .push(
             Router::with_path("api")
                 .hoop(auth_middleware)
-                .push(Router::with_path("tasks").get(list_tasks).post(create_task))
+                .hoop(session_guard)
+                .push(Router::with_path("account/logout").post(logout))
+                .push(Router::with_path("family").get(get_family))
+                .push(
+                    Router::with_path("family/children")
+                        .hoop(require(Permission::FamilyManage))
+                        .get(list_children)
+                        .post(create_child),
+                )
+                .push(
+                    Router::with_path("family/children/{id}")
+                        .hoop(require(Permission::FamilyManage))
+                        .put(update_child)
+                        .delete(delete_child),
+                )
+                .push(
+                    Router::with_path("tasks").get(list_tasks).push(
+                        Router::new()
+                            .hoop(require(Permission::TaskWrite))
+                            .post(create_task),
+                    ),
+                )
+                .push(Router::with_path("tasks/options").get(task_options))
+                .push(
+                    Router::with_path("resources").get(list_resources).push(
+                        Router::new()
+                            .hoop(require(Permission::ResourceWrite))
+                            .post(upload_resource),
+                    ),
+                )
+                .push(Router::with_path("resources/usage").get(storage_usage))
+                .push(Router::with_path("jobs").get(list_jobs))
+                .push(
+                    Router::with_path("jobs/{id}").get(get_job).push(
+                        Router::with_path("retry")
+                            .hoop(require(Permission::TaskWrite))
+                            .post(retry_job),
+                    ),
+                )
+                .push(
+                    Router::with_path("task-drafts").get(list_drafts).push(
+                        Router::new()
+                            .hoop(require(Permission::TaskWrite))
+                            .push(Router::with_path("accept").post(accept_drafts))
+                            .push(Router::with_path("reject").post(reject_drafts))
+                            .push(Router::with_path("{id}").patch(patch_draft)),
+                    ),
+                )
+                .push(
+                    Router::with_path("resources/{id}")
+                        .get(get_resource)
+                        .push(Router::with_path("download").get(download_resource))
+                        .push(Router::with_path("signed-url").get(create_download_url))
+                        .push(Router::with_path("tasks").get(list_resource_tasks))
+                        .push(
+                            Router::with_path("extract")
+                                .hoop(require(Permission::TaskWrite))
+                                .post(extract_tasks),
+                        )
+                        .push(
+                            Router::new()
+                                .hoop(require(Permission::ResourceWrite))
+                                .delete(delete_resource),
+                        ),
+                )
                 .push(
                     Router::with_path("tasks/{id}")
                         .get(get_task)
-                        .put(update_task)
-                        .delete(delete_task),
+                        .push(
+                            Router::new()
+                                .hoop(require(Permission::TaskWrite))
+                                .put(update_task)
+                                .patch(patch_task),
+                        )
+                        .push(
+                            Router::new()
+                                .hoop(require(Permission::TaskDelete))
+                                .delete(delete_task),
+                        )
+                        .push(
+                            Router::new()
+                                .hoop(require(Permission::TaskToggleStatus))
+                                .push(Router::with_path("complete").post(complete_task))
+                                .push(Router::with_path("reopen").post(reopen_task)),
+                        )
+                        .push(
+                            Router::new()
+                                .hoop(require(Permission::TaskArchive))
+                                .push(Router::with_path("archive").post(archive_task))
+                                .push(Router::with_path("unarchive").post(unarchive_task)),
+                        )
+                        .push(Router::with_path("children").get(list_task_children))
+                        .push(Router::with_path("parents").get(list_parents))
+                        .push(
+                            Router::with_path("links")
+                                .hoop(require(Permission::TaskWrite))
+                                .post(create_link)
+                                .push(Router::with_path("{child_id}").delete(delete_link)),
+                        )
+                        .push(
+                            Router::with_path("resources")
+                                .get(list_task_resources)
+                                .push(
+                                    Router::new()
+                                        .hoop(require(Permission::TaskWrite))
+                                        .post(attach_resource)
+                                        .push(
+                                            Router::with_path("{resource_id}")
+                                                .delete(detach_resource),
+                                        ),
+                                ),
+                        ),
                 ),
         );
 
+    // CORS 挂在 Service 上：预检请求（OPTIONS）没有对应路由，也会先经过 CORS 处理并直接返回
+    let mut service = Service::new(router);
+    if let Some(cors) = create_cors(&config.cors_allowed_origins) {
+        service = service.hoop_when(cors, |req, _| req.uri().path().starts_with("/api/"));
+    }
+
     // 启动服务器
     let addr: String = format!("{}:{}", config.server_host, config.server_port);
     let acceptor = TcpListener::new(addr).bind().await;
-    Server::new(acceptor).serve(router).await;
+    let server = Server::new(acceptor);
+    let handle = server.handle();
+
+    // 收到 SIGTERM / SIGINT：停止接收新连接，宽限期内等待进行中的请求完成，超时后强制断开；
+    // 同时通知 worker 不再领取新任务
+    let grace = Duration::from_secs(config.shutdown_grace_secs);
+    let shutdown_tx = Arc::new(shutdown_tx);
+    let deadline = Arc::new(OnceLock::new());
+    tokio::spawn({
+        let shutdown_tx = shutdown_tx.clone();
+        let deadline = deadline.clone();
+        async move {
+            wait_for_signal().await;
+            tracing::info!(grace_secs = grace.as_secs(), "正在停止服务");
+            let _ = deadline.set(Instant::now() + grace);
+            handle.stop_graceful(grace);
+            let _ = shutdown_tx.send(true);
+        }
+    });
+    server.serve(service).await;
+    tracing::info!("HTTP 服务已停止");
+
+    // 等待 worker 执行完手上的任务（与 HTTP 共用同一个宽限期），
+    // 超时的任务中止，由超时回收重新排队
+    let _ = shutdown_tx.send(true);
+    let deadline = deadline
+        .get()
+        .copied()
+        .unwrap_or_else(|| Instant::now() + grace);
+    let drained = tokio::time::timeout_at(deadline, async {
+        for worker in &mut workers {
+            if let Err(e) = worker.await {
+                tracing::error!(err = ?e, "worker 异常退出");
+            }
+        }
+    })
+    .await;
+    if drained.is_err() {
+        tracing::warn!("等待后台任务超时，强制中止");
+        for worker in &workers {
+            worker.abort();
+        }
+    }
+
+    // 关闭数据库连接池，等待连接归还后断开
+    pool.close().await;
+    tracing::info!("服务已退出");
 
     Ok(())
 }
@@ -1,9 +1,101 @@
+use crate::error::AppError;
+use crate::handler::DepotExt;
 use crate::model::account::Claims;
 use salvo::jwt_auth::{ConstDecoder, HeaderFinder};
 use salvo::prelude::JwtAuth;
+use salvo::{handler, Depot, FlowCtrl, Request, Response, Writer};
 
 pub fn create_jwt_auth(secret: &str) -> JwtAuth<Claims, ConstDecoder> {
     JwtAuth::new(ConstDecoder::from_secret(secret.as_bytes()))
         .finders(vec![Box::new(HeaderFinder::new())])
         .force_passed(false)
-}
\ No newline at end of file
+}
+
+/// 会话校验：拒绝已注销 / 已吊销会话的 token，需挂在 JwtAuth 之后
+#[handler]
+pub async fn session_guard(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+    ctrl: &mut FlowCtrl,
+) {
+    if let Err(e) = check_session(depot).await {
+        e.write(req, depot, res).await;
+        ctrl.skip_rest();
+    }
+}
+
+async fn check_session(depot: &Depot) -> Result<(), AppError> {
+    let session_id = depot.current_session_id()?;
+    let ctx = depot.app_context()?;
+    if ctx.services.session.is_active(session_id).await? {
+        Ok(())
+    } else {
+        Err(AppError::Unauthorized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::context::{AppContext, Repos};
+    use crate::handler::account::logout;
+    use crate::test_support::{config, create_parent, local_storage};
+    use salvo::affix_state;
+    use salvo::prelude::{Router, Service, StatusCode};
+    use salvo::test::TestClient;
+    use sqlx::PgPool;
+    use std::sync::Arc;
+
+    #[handler]
+    async fn ping() -> &'static str {
+        "pong"
+    }
+
+    async fn status(service: &Service, method: &str, path: &str, token: &str) -> StatusCode {
+        let url = format!("http://127.0.0.1{}", path);
+        let request = match method {
+            "POST" => TestClient::post(url),
+            _ => TestClient::get(url),
+        };
+        request
+            .bearer_auth(token)
+            .send(service)
+            .await
+            .status_code
+            .unwrap_or(StatusCode::OK)
+    }
+
+    #[sqlx::test]
+    async fn logout_rejects_access_token(pool: PgPool) {
+        let config = config();
+        let repos = Repos::new(pool.clone());
+        let account_id = create_parent(&repos).await;
+        let account = repos.account.get_by_id(account_id).await.unwrap();
+        let ctx = Arc::new(AppContext::new(pool, local_storage().await, None, None));
+        let tokens = ctx.services.session.start(&config, &account).await.unwrap();
+
+        let router = Router::new()
+            .hoop(affix_state::inject(ctx))
+            .hoop(create_jwt_auth(&config.jwt_secret))
+            .hoop(session_guard)
+            .push(Router::with_path("ping").get(ping))
+            .push(Router::with_path("account/logout").post(logout));
+        let service = Service::new(router);
+        let token = &tokens.access_token;
+
+        assert_eq!(
+            status(&service, "GET", "/ping", token).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&service, "POST", "/account/logout", token).await,
+            StatusCode::OK
+        );
+        // access token 尚未过期，但所属会话已吊销
+        assert_eq!(
+            status(&service, "GET", "/ping", token).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use crate::error::AppError;
+use salvo::http::header::CONTENT_LENGTH;
+use salvo::prelude::*;
+
+/// 请求体大小限制 hoop（REQUEST_BODY_LIMIT）
+///
+/// Content-Length 超限直接返回 413；同时设置 secure_max_size，
+/// 约束 JSON / 表单等直接读取 body 的解析。multipart 文件由 salvo 落盘，
+/// 不受 secure_max_size 约束，上传处理中会再按文件大小校验
+pub struct BodyLimit(usize);
+
+pub fn body_limit(limit: usize) -> BodyLimit {
+    BodyLimit(limit)
+}
+
+#[async_trait]
+impl Handler for BodyLimit {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let content_length = req.header::<usize>(CONTENT_LENGTH).unwrap_or_default();
+        if content_length > self.0 {
+            AppError::PayloadTooLarge(self.0)
+                .write(req, depot, res)
+                .await;
+            ctrl.skip_rest();
+            return;
+        }
+        req.set_secure_max_size(self.0);
+    }
+}
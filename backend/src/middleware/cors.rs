@@ -0,0 +1,52 @@
+use salvo::cors::{AllowOrigin, Cors, CorsHandler};
+use salvo::http::header::{self, HeaderValue};
+use salvo::http::Method;
+
+// 预检结果缓存时间（秒），减少浏览器重复发送 OPTIONS
+const PREFLIGHT_MAX_AGE_SECS: u64 = 86400;
+
+/// 按 CORS_ALLOWED_ORIGINS 创建跨域中间件，未配置来源时返回 None（不启用）
+///
+/// 前端携带 Cookie / Authorization 时需要 allow_credentials，此时响应不能使用 `*`，
+/// 所以开发环境的 `*` 以回显请求 Origin 的方式实现；生产环境只允许列出的来源
+pub fn create_cors(allowed_origins: &[String]) -> Option<CorsHandler> {
+    if allowed_origins.is_empty() {
+        return None;
+    }
+
+    let allow_origin = if allowed_origins.iter().any(|o| o == "*") {
+        AllowOrigin::mirror_request()
+    } else {
+        AllowOrigin::list(
+            allowed_origins
+                .iter()
+                .filter_map(|o| HeaderValue::from_str(o).ok()),
+        )
+    };
+
+    let cors = Cors::new()
+        .allow_origin(allow_origin)
+        .allow_credentials(true)
+        .allow_methods(vec![
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers(vec![
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            header::RANGE,
+        ])
+        // 下载文件时前端需要读取文件名和分段信息
+        .expose_headers(vec![
+            header::CONTENT_DISPOSITION,
+            header::CONTENT_RANGE,
+            header::ACCEPT_RANGES,
+        ])
+        .max_age(PREFLIGHT_MAX_AGE_SECS);
+
+    Some(cors.into_handler())
+}
@@ -1 +1,4 @@
 pub mod auth;
+pub mod body_limit;
+pub mod cors;
+pub mod permission;
@@ -0,0 +1,53 @@
+use crate::handler::DepotExt;
+use crate::model::account::Role;
+use salvo::{async_trait, Depot, FlowCtrl, Handler, Request, Response, Writer};
+
+/// 接口权限点，路由上通过 `require(...)` 声明
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// 创建 / 编辑任务
+    TaskWrite,
+    /// 切换任务完成状态（active <-> done）
+    TaskToggleStatus,
+    /// 归档 / 取消归档任务（含计划）
+    TaskArchive,
+    /// 删除任务
+    TaskDelete,
+    /// 管理家庭中的孩子账户
+    FamilyManage,
+    /// 上传 / 删除资源
+    ResourceWrite,
+}
+
+impl Permission {
+    pub fn allowed_for(self, role: Role) -> bool {
+        match role {
+            Role::Parent => true,
+            // 孩子只能查看任务并切换完成状态
+            Role::Child => matches!(self, Permission::TaskToggleStatus),
+        }
+    }
+}
+
+/// 权限校验 hoop，需挂在 JwtAuth 之后
+pub struct RequirePermission(Permission);
+
+pub fn require(permission: Permission) -> RequirePermission {
+    RequirePermission(permission)
+}
+
+#[async_trait]
+impl Handler for RequirePermission {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        if let Err(e) = depot.require_permission(self.0) {
+            e.write(req, depot, res).await;
+            ctrl.skip_rest();
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use crate::app::cli::MigrateCommand;
+use crate::config::RuntimeEnv;
+use sqlx::migrate::{Migrate, MigrateError, Migrator};
+use sqlx::{Connection, PgConnection, PgPool};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// 编译进二进制的迁移脚本（backend/migrations，每个版本一对 up / down）
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// 单个迁移版本的状态（migrate status）
+#[derive(Debug)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    Modified, // 已执行，但脚本内容与执行时不一致
+    Missing,  // 数据库中已执行，但当前二进制中没有（通常是用旧版本启动）
+    Dirty,    // 执行失败，需人工处理
+}
+
+impl MigrationState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MigrationState::Applied => "applied",
+            MigrationState::Pending => "pending",
+            MigrationState::Modified => "modified",
+            MigrationState::Missing => "missing",
+            MigrationState::Dirty => "dirty",
+        }
+    }
+}
+
+impl fmt::Display for MigrationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}  {:<8}  {}",
+            self.version,
+            self.state.as_str(),
+            self.description
+        )
+    }
+}
+
+/// 执行 migrate 子命令，结果输出到标准输出
+pub async fn run_command(
+    pool: &PgPool,
+    rust_env: RuntimeEnv,
+    command: MigrateCommand,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        MigrateCommand::Up => {
+            let applied = run_pending(pool).await?;
+            if applied.is_empty() {
+                println!("数据库已是最新，无需迁移");
+            } else {
+                println!("已执行迁移: {:?}", applied);
+            }
+        }
+        MigrateCommand::Status => {
+            for status in status(pool).await? {
+                println!("{}", status);
+            }
+        }
+        MigrateCommand::DownTo { version, confirmed } => {
+            // 回滚会删除表和字段（连同数据），生产环境需显式确认
+            if rust_env == RuntimeEnv::Production && !confirmed {
+                return Err("生产环境回滚会删除数据，确认后加 --yes 执行".into());
+            }
+            let reverted = revert_to(pool, version).await?;
+            if reverted.is_empty() {
+                println!("没有需要回滚的迁移");
+            } else {
+                println!("已回滚迁移: {:?}", reverted);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 执行全部未应用的迁移，返回本次执行的版本。
+/// Migrator 执行期间持有会话级 advisory lock，多个实例同时启动时串行执行，后拿到锁的实例发现已是最新，直接跳过
+pub async fn run_pending(pool: &PgPool) -> Result<Vec<i64>, MigrateError> {
+    let mut conn = pool.acquire().await?.detach();
+    let result = async {
+        let before = applied_versions(&mut conn).await?;
+        MIGRATOR.run(&mut conn).await?;
+        let after = applied_versions(&mut conn).await?;
+        Ok(after.into_iter().filter(|v| !before.contains(v)).collect())
+    }
+    .await;
+    close(conn).await;
+    result
+}
+
+/// 回滚到指定版本：依次执行比 target 新的已应用版本的 down 脚本（倒序），返回回滚的版本。
+/// target 为 0 时回滚全部
+pub async fn revert_to(pool: &PgPool, target: i64) -> Result<Vec<i64>, MigrateError> {
+    if target != 0 && !MIGRATOR.version_exists(target) {
+        return Err(MigrateError::VersionNotPresent(target));
+    }
+
+    let mut conn = pool.acquire().await?.detach();
+    let result = async {
+        let before = applied_versions(&mut conn).await?;
+        MIGRATOR.undo(&mut conn, target).await?;
+        let after = applied_versions(&mut conn).await?;
+        let mut reverted: Vec<i64> = before.into_iter().filter(|v| !after.contains(v)).collect();
+        reverted.reverse();
+        Ok(reverted)
+    }
+    .await;
+    close(conn).await;
+    result
+}
+
+/// 对比二进制中的迁移与数据库中的执行记录（_sqlx_migrations），按版本排序
+pub async fn status(pool: &PgPool) -> Result<Vec<MigrationStatus>, MigrateError> {
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+    let dirty = conn.dirty_version().await?;
+    let mut applied: HashMap<i64, Vec<u8>> = conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|m| (m.version, m.checksum.into_owned()))
+        .collect();
+
+    let mut statuses: Vec<MigrationStatus> = MIGRATOR
+        .iter()
+        .filter(|m| m.migration_type.is_up_migration())
+        .map(|m| {
+            let state = match applied.remove(&m.version) {
+                _ if dirty == Some(m.version) => MigrationState::Dirty,
+                Some(checksum) if checksum == *m.checksum => MigrationState::Applied,
+                Some(_) => MigrationState::Modified,
+                None => MigrationState::Pending,
+            };
+            MigrationStatus {
+                version: m.version,
+                description: m.description.to_string(),
+                state,
+            }
+        })
+        .collect();
+    statuses.extend(applied.into_keys().map(|version| MigrationStatus {
+        version,
+        description: String::new(),
+        state: if dirty == Some(version) {
+            MigrationState::Dirty
+        } else {
+            MigrationState::Missing
+        },
+    }));
+    statuses.sort_by_key(|s| s.version);
+    Ok(statuses)
+}
+
+// 迁移使用不归还连接池的连接：Migrator 执行失败时直接返回，不会释放 advisory lock，
+// 断开连接才能确保锁随之释放
+async fn close(conn: PgConnection) {
+    if let Err(e) = conn.close().await {
+        tracing::warn!(err = ?e, "关闭迁移连接失败");
+    }
+}
+
+async fn applied_versions(conn: &mut PgConnection) -> Result<Vec<i64>, MigrateError> {
+    conn.ensure_migrations_table().await?;
+    Ok(conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|m| m.version)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn table_exists(pool: &PgPool, name: &str) -> bool {
+        sqlx::query_scalar::<_, bool>("SELECT to_regclass($1) IS NOT NULL")
+            .bind(name)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    fn states(statuses: &[MigrationStatus]) -> Vec<MigrationState> {
+        statuses.iter().map(|s| s.state).collect()
+    }
+
+    // 每个 down 脚本都能撤销对应的 up 脚本，撤销后可以重新执行
+    #[sqlx::test]
+    async fn revert_all_then_reapply(pool: PgPool) {
+        let versions: Vec<i64> = status(&pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|s| s.version)
+            .collect();
+        assert!(states(&status(&pool).await.unwrap())
+            .iter()
+            .all(|s| *s == MigrationState::Applied));
+
+        let reverted = revert_to(&pool, 0).await.unwrap();
+        let mut newest_first = versions.clone();
+        newest_first.reverse();
+        assert_eq!(reverted, newest_first);
+        assert!(!table_exists(&pool, "account").await);
+        assert!(!table_exists(&pool, "job").await);
+        assert!(states(&status(&pool).await.unwrap())
+            .iter()
+            .all(|s| *s == MigrationState::Pending));
+
+        assert_eq!(run_pending(&pool).await.unwrap(), versions);
+        assert!(table_exists(&pool, "job").await);
+        assert!(run_pending(&pool).await.unwrap().is_empty());
+    }
+
+    #[sqlx::test]
+    async fn revert_to_keeps_target_version(pool: PgPool) {
+        let reverted = revert_to(&pool, 9).await.unwrap();
+        assert_eq!(reverted, [11, 10]);
+        assert!(!table_exists(&pool, "task_draft").await);
+        assert!(table_exists(&pool, "resource").await);
+
+        assert!(matches!(
+            revert_to(&pool, 999).await,
+            Err(MigrateError::VersionNotPresent(999))
+        ));
+    }
+}
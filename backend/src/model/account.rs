@@ -1,7 +1,25 @@
+use crate::error::AppError;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+// 账户角色，对应 chk_account_role
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Parent,
+    Child,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Parent => "parent",
+            Role::Child => "child",
+        }
+    }
+}
+
 // 注册请求
 #[derive(Debug, Deserialize)]
 pub struct RegisterRequest {
@@ -16,6 +34,8 @@ pub struct AccountResponse {
     pub id: Uuid,
     pub username: Option<String>,
     pub nickname: String,
+    pub role: Role,
+    pub family_id: Uuid,
 }
 
 // 登录请求
@@ -29,12 +49,17 @@ pub struct LoginRequest {
 #[derive(Debug, Serialize)]
 pub struct LoginResponse {
     pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in: i64, // access token 有效期（秒）
     pub account: AccountResponse,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String, // subject: account_id
+    pub sid: String, // session id，用于服务端吊销
+    pub role: Role,  // 角色，用于接口鉴权
+    pub fid: String, // family id
     pub exp: usize,  // 过期时间（时间戳）
 }
 
@@ -52,6 +77,40 @@ pub struct Account {
     pub created_by: Uuid,
     pub updated_by: Uuid,
     pub is_deleted: bool,
+    pub family_id: Uuid,
+}
+
+impl Account {
+    /// 角色只有 parent / child（chk_account_role），其它取值视为数据异常，不按孩子处理
+    pub fn role(&self) -> Result<Role, AppError> {
+        match self.role.as_str() {
+            "parent" => Ok(Role::Parent),
+            "child" => Ok(Role::Child),
+            other => {
+                tracing::error!(account_id = %self.id, role = other, "账户角色取值异常");
+                Err(AppError::Internal)
+            }
+        }
+    }
+
+    pub fn is_parent(&self) -> Result<bool, AppError> {
+        Ok(self.role()? == Role::Parent)
+    }
+}
+
+impl TryFrom<Account> for AccountResponse {
+    type Error = AppError;
+
+    fn try_from(a: Account) -> Result<Self, AppError> {
+        let role = a.role()?;
+        Ok(AccountResponse {
+            id: a.id,
+            username: a.username,
+            nickname: a.nickname,
+            role,
+            family_id: a.family_id,
+        })
+    }
 }
 
 pub struct AccountCreate {
@@ -65,4 +124,5 @@ pub struct AccountCreate {
     pub created_by: Uuid,
     pub updated_by: Uuid,
     pub is_deleted: bool,
+    pub family_id: Uuid,
 }
@@ -0,0 +1,47 @@
+use crate::model::account::AccountResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// 家庭信息 + 成员
+#[derive(Debug, Serialize)]
+pub struct FamilyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub members: Vec<AccountResponse>,
+}
+
+// 家长创建孩子账户
+#[derive(Debug, Deserialize)]
+pub struct CreateChildRequest {
+    pub username: String,
+    pub password: String,
+    pub nickname: String,
+}
+
+// 家长修改孩子账户（昵称 / 重置密码）
+#[derive(Debug, Deserialize)]
+pub struct UpdateChildRequest {
+    pub nickname: Option<String>,
+    pub password: Option<String>,
+}
+
+// 数据库对应的实体
+#[derive(Debug, sqlx::FromRow)]
+#[expect(dead_code)]
+pub struct Family {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Uuid,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: Uuid,
+    pub is_deleted: bool,
+}
+
+pub struct FamilyCreate {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Uuid,
+}
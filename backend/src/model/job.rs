@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+// 任务状态，对应 chk_job_status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Dead, // 重试耗尽（死信）
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Dead => "dead",
+        }
+    }
+}
+
+/// 任务类型及参数，整体序列化后存入 payload，kind 列冗余一份便于查询
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobPayload {
+    // AI 识别资源中的任务，生成草稿
+    ExtractTasks { resource_id: Uuid, account_id: Uuid },
+    // 生成资源缩略图
+    Thumbnail { resource_id: Uuid },
+    // OCR / PDF 提取资源文字
+    ExtractText { resource_id: Uuid },
+}
+
+impl JobPayload {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            JobPayload::ExtractTasks { .. } => "extract_tasks",
+            JobPayload::Thumbnail { .. } => "thumbnail",
+            JobPayload::ExtractText { .. } => "extract_text",
+        }
+    }
+
+    /// 最多执行次数（含首次），超过后进入死信
+    pub fn max_attempts(&self) -> i32 {
+        match self {
+            JobPayload::ExtractTasks { .. } => 3, // 每次调用 LLM 都有成本
+            JobPayload::Thumbnail { .. } | JobPayload::ExtractText { .. } => 5,
+        }
+    }
+}
+
+// 数据库对应的实体
+#[derive(Debug, sqlx::FromRow)]
+pub struct Job {
+    pub id: Uuid,
+    pub kind: String,
+    pub payload: Value,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: DateTime<Utc>,
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub locked_at: Option<DateTime<Utc>>,
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub locked_by: Option<String>,
+    pub last_error: Option<String>,
+    pub result: Option<Value>,
+    #[expect(dead_code)]
+    pub account_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    #[expect(dead_code)]
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+// 列表查询参数：GET /api/jobs?status=dead&kind=extract_tasks
+#[derive(Debug, Deserialize)]
+pub struct JobListQuery {
+    pub status: Option<JobStatus>,
+    pub kind: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobResponse {
+    pub id: Uuid,
+    pub kind: String,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub result: Option<Value>,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl From<Job> for JobResponse {
+    fn from(j: Job) -> Self {
+        JobResponse {
+            id: j.id,
+            kind: j.kind,
+            status: j.status,
+            attempts: j.attempts,
+            max_attempts: j.max_attempts,
+            run_at: j.run_at,
+            last_error: j.last_error,
+            result: j.result,
+            created_at: j.created_at,
+            finished_at: j.finished_at,
+        }
+    }
+}
@@ -1,2 +1,10 @@
 pub mod account;
+pub mod family;
+pub mod job;
+pub mod patch;
+pub mod resource;
+pub mod session;
 pub mod task;
+pub mod task_draft;
+pub mod task_link;
+pub mod task_resource;
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Deserializer};
+
+/// JSON Merge Patch 字段：缺省 = 不修改，null = 清空，有值 = 更新
+///
+/// 结构体字段需配合 `#[serde(default)]`，否则缺省字段会反序列化失败
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Patch<T> {
+    #[default]
+    Missing,
+    Null,
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Patch::Missing)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Patch::Null)
+    }
+
+    /// 要写入的新值（Null 时为 None），Missing 时调用方应保持原值
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Patch::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+// PUT 语义：None 视为不修改
+impl<T> From<Option<T>> for Patch<T> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(v) => Patch::Value(v),
+            None => Patch::Missing,
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(v) => Patch::Value(v),
+            None => Patch::Null,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Body {
+        #[serde(default)]
+        note: Patch<String>,
+    }
+
+    fn note(json: &str) -> Patch<String> {
+        serde_json::from_str::<Body>(json).unwrap().note
+    }
+
+    #[test]
+    fn missing_null_and_value_are_distinct() {
+        let missing = note("{}");
+        assert!(missing.is_missing());
+        assert_eq!(missing.value(), None);
+
+        let null = note(r#"{"note":null}"#);
+        assert!(null.is_null());
+        assert!(!null.is_missing());
+        assert_eq!(null.value(), None);
+
+        let value = note(r#"{"note":"带计算器"}"#);
+        assert!(!value.is_missing() && !value.is_null());
+        assert_eq!(value.value().map(String::as_str), Some("带计算器"));
+        // 空字符串是值，不是清空
+        assert_eq!(note(r#"{"note":""}"#), Patch::Value(String::new()));
+    }
+
+    #[test]
+    fn wrong_type_is_rejected() {
+        assert!(serde_json::from_str::<Body>(r#"{"note":1}"#).is_err());
+    }
+
+    #[test]
+    fn put_none_means_unchanged() {
+        assert_eq!(Patch::from(None::<String>), Patch::Missing);
+        assert_eq!(Patch::from(Some(1)), Patch::Value(1));
+    }
+}
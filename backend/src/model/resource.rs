@@ -0,0 +1,262 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// 文件类型，对应 chk_resource_file_type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+pub enum FileType {
+    Pdf,
+    Image,
+    Doc,
+    Other,
+}
+
+impl FileType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileType::Pdf => "pdf",
+            FileType::Image => "image",
+            FileType::Doc => "doc",
+            FileType::Other => "other",
+        }
+    }
+
+    /// 配置项中的类型名（pdf / image / doc / other）
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "pdf" => Some(FileType::Pdf),
+            "image" => Some(FileType::Image),
+            "doc" => Some(FileType::Doc),
+            "other" => Some(FileType::Other),
+            _ => None,
+        }
+    }
+
+    /// 按 MIME 类型归类
+    pub fn from_mime(mime: &str) -> Self {
+        match mime {
+            "application/pdf" => FileType::Pdf,
+            m if m.starts_with("image/") => FileType::Image,
+            "application/msword"
+            | "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+            | "application/vnd.ms-excel"
+            | "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            | "application/vnd.ms-powerpoint"
+            | "application/vnd.openxmlformats-officedocument.presentationml.presentation"
+            | "text/plain" => FileType::Doc,
+            _ => FileType::Other,
+        }
+    }
+}
+
+// 数据库对应的实体
+#[derive(Debug, sqlx::FromRow)]
+#[expect(dead_code)]
+pub struct Resource {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub file_name: String,
+    pub file_type: FileType,
+    pub storage_url: String,
+    pub content_hash: Option<String>, // SHA-256，历史数据为空
+    pub file_size: i64,
+    pub mime_type: Option<String>, // 上传时按文件头识别，历史数据为空
+    pub extracted_text: Option<String>, // OCR / PDF 提取的文字，空字符串表示已提取但没有文字
+    pub thumbnail_key: Option<String>, // 缩略图，后台生成完成前为空
+    pub created_at: DateTime<Utc>,
+    pub created_by: Uuid,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: Uuid,
+    pub is_deleted: bool,
+}
+
+impl Resource {
+    /// 存储中的对象 key：按内容寻址，同一账户内相同内容共用一个对象
+    pub fn storage_key(&self) -> String {
+        match &self.content_hash {
+            Some(hash) => blob_key(self.account_id, hash),
+            None => format!("resources/{}/{}", self.account_id, self.id),
+        }
+    }
+
+    /// 下载时使用的 Content-Type：上传时识别出的类型，不按文件名推断
+    pub fn content_type(&self) -> String {
+        self.mime_type
+            .clone()
+            .unwrap_or_else(|| "application/octet-stream".to_string())
+    }
+}
+
+pub fn blob_key(account_id: Uuid, content_hash: &str) -> String {
+    format!("blobs/{}/{}", account_id, content_hash)
+}
+
+/// 缩略图与原文件放在一起，相同内容的资源共用
+pub fn thumbnail_key(storage_key: &str) -> String {
+    format!("{}.thumb.jpg", storage_key)
+}
+
+// 上传后待入库的资源
+pub struct ResourceCreate {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub file_name: String,
+    pub file_type: FileType,
+    pub storage_url: String,
+    pub content_hash: String,
+    pub file_size: i64,
+    pub mime_type: String, // sniff 识别出的类型
+    pub created_at: DateTime<Utc>,
+    pub created_by: Uuid,
+}
+
+// 上传的文件内容，由 handler 从 multipart 中读取；客户端声明的 Content-Type 不可信，不保留
+pub struct UploadFile {
+    pub file_name: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResourceResponse {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub file_name: String,
+    pub file_type: FileType,
+    pub file_size: i64,
+    pub mime_type: String,
+    pub has_text: bool, // 是否已提取文字
+    pub has_thumbnail: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>, // 签名链接，由 handler 签发
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Resource> for ResourceResponse {
+    fn from(r: Resource) -> Self {
+        let mime_type = r.content_type();
+        ResourceResponse {
+            id: r.id,
+            account_id: r.account_id,
+            file_name: r.file_name,
+            file_type: r.file_type,
+            file_size: r.file_size,
+            mime_type,
+            has_text: r.extracted_text.as_deref().is_some_and(|t| !t.is_empty()),
+            has_thumbnail: r.thumbnail_key.is_some(),
+            thumbnail_url: None,
+            created_at: r.created_at,
+        }
+    }
+}
+
+// 资源详情，包含提取的文字
+#[derive(Debug, Serialize)]
+pub struct ResourceDetailResponse {
+    #[serde(flatten)]
+    pub resource: ResourceResponse,
+    pub extracted_text: Option<String>,
+}
+
+impl From<Resource> for ResourceDetailResponse {
+    fn from(mut r: Resource) -> Self {
+        // 空字符串表示已提取但没有文字
+        let extracted_text = r.extracted_text.take().filter(|t| !t.is_empty());
+        let mut resource: ResourceResponse = r.into();
+        resource.has_text = extracted_text.is_some();
+        ResourceDetailResponse {
+            resource,
+            extracted_text,
+        }
+    }
+}
+
+// 列表查询参数：GET /api/resources?file_type=pdf&q=答案&limit=20&cursor=...
+#[derive(Debug, Deserialize)]
+pub struct ResourceListQuery {
+    pub account_id: Option<Uuid>, // 家长按孩子筛选
+    pub file_type: Option<FileType>,
+    pub q: Option<String>, // 文件名模糊搜索
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+// 分页游标：按 created_at 倒序
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourceCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl ResourceCursor {
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    pub fn decode(raw: &str) -> Option<Self> {
+        let json = URL_SAFE_NO_PAD.decode(raw).ok()?;
+        serde_json::from_slice(&json).ok()
+    }
+}
+
+// GET /api/resources/usage?account_id=...，家长可查看孩子的用量
+#[derive(Debug, Deserialize)]
+pub struct StorageUsageQuery {
+    pub account_id: Option<Uuid>,
+}
+
+// 存储空间使用情况
+#[derive(Debug, Serialize)]
+pub struct StorageUsageResponse {
+    pub used_bytes: i64,
+    pub quota_bytes: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResourcePage {
+    pub items: Vec<ResourceResponse>,
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mime_maps_to_file_type() {
+        let cases = [
+            ("application/pdf", FileType::Pdf),
+            ("image/png", FileType::Image),
+            ("image/heic", FileType::Image),
+            ("application/msword", FileType::Doc),
+            (
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+                FileType::Doc,
+            ),
+            ("text/plain", FileType::Doc),
+            ("application/zip", FileType::Other),
+            ("application/octet-stream", FileType::Other),
+        ];
+        for (mime, expected) in cases {
+            assert_eq!(FileType::from_mime(mime), expected, "{}", mime);
+        }
+    }
+
+    #[test]
+    fn type_names_round_trip() {
+        for file_type in [
+            FileType::Pdf,
+            FileType::Image,
+            FileType::Doc,
+            FileType::Other,
+        ] {
+            assert_eq!(FileType::parse(file_type.as_str()), Some(file_type));
+        }
+        assert_eq!(FileType::parse("PDF"), None);
+        assert_eq!(FileType::parse("video"), None);
+    }
+}
@@ -1,24 +1,201 @@
+use crate::model::patch::Patch;
+use crate::model::resource::ResourceResponse;
+use crate::model::task_link::TaskTreeNode;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, sqlx::FromRow)]
+// 任务类型，对应 chk_task_type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+pub enum TaskType {
+    Homework,
+    Practice,
+    Preview,
+    Review,
+    Plan,
+}
+
+impl TaskType {
+    pub const ALL: [TaskType; 5] = [
+        TaskType::Homework,
+        TaskType::Practice,
+        TaskType::Preview,
+        TaskType::Review,
+        TaskType::Plan,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskType::Homework => "homework",
+            TaskType::Practice => "practice",
+            TaskType::Preview => "preview",
+            TaskType::Review => "review",
+            TaskType::Plan => "plan",
+        }
+    }
+}
+
+// 学科，对应 chk_task_subject
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+pub enum Subject {
+    Math,
+    Chinese,
+    English,
+    Physics,
+    Chemistry,
+    Programming,
+    Other,
+}
+
+impl Subject {
+    pub const ALL: [Subject; 7] = [
+        Subject::Math,
+        Subject::Chinese,
+        Subject::English,
+        Subject::Physics,
+        Subject::Chemistry,
+        Subject::Programming,
+        Subject::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Subject::Math => "math",
+            Subject::Chinese => "chinese",
+            Subject::English => "english",
+            Subject::Physics => "physics",
+            Subject::Chemistry => "chemistry",
+            Subject::Programming => "programming",
+            Subject::Other => "other",
+        }
+    }
+}
+
+// 任务状态，对应 chk_task_status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+pub enum TaskStatus {
+    Active,
+    Done,
+    Archived,
+}
+
+impl TaskStatus {
+    pub const ALL: [TaskStatus; 3] = [TaskStatus::Active, TaskStatus::Done, TaskStatus::Archived];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Active => "active",
+            TaskStatus::Done => "done",
+            TaskStatus::Archived => "archived",
+        }
+    }
+}
+
+// 任务来源，对应 chk_task_source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+pub enum TaskSource {
+    Manual,
+    AiExtract,
+    Wechat,
+    Dingtalk,
+}
+
+impl TaskSource {
+    pub const ALL: [TaskSource; 4] = [
+        TaskSource::Manual,
+        TaskSource::AiExtract,
+        TaskSource::Wechat,
+        TaskSource::Dingtalk,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskSource::Manual => "manual",
+            TaskSource::AiExtract => "ai_extract",
+            TaskSource::Wechat => "wechat",
+            TaskSource::Dingtalk => "dingtalk",
+        }
+    }
+}
+
+// 状态流转操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskTransition {
+    Complete,
+    Reopen,
+    Archive,
+    Unarchive,
+}
+
+impl TaskTransition {
+    /// 允许的流转：
+    /// active -> done（完成），done -> active（重新打开），
+    /// active / done -> archived（归档），archived -> 归档前的状态（取消归档）
+    pub fn target(&self, task: &Task) -> Option<TaskStatus> {
+        match (self, task.status) {
+            (TaskTransition::Complete, TaskStatus::Active) => Some(TaskStatus::Done),
+            (TaskTransition::Reopen, TaskStatus::Done) => Some(TaskStatus::Active),
+            (TaskTransition::Archive, TaskStatus::Active | TaskStatus::Done) => {
+                Some(TaskStatus::Archived)
+            }
+            (TaskTransition::Unarchive, TaskStatus::Archived) => match task.completed_at {
+                Some(_) => Some(TaskStatus::Done),
+                None => Some(TaskStatus::Active),
+            },
+            _ => None,
+        }
+    }
+
+    /// 流转后的 completed_at：完成时写入，重新打开时清空，归档 / 取消归档保持不变
+    pub fn completed_at(&self, task: &Task, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            TaskTransition::Complete => Some(now),
+            TaskTransition::Reopen => None,
+            TaskTransition::Archive | TaskTransition::Unarchive => task.completed_at,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TaskTransition::Complete => "完成",
+            TaskTransition::Reopen => "重新打开",
+            TaskTransition::Archive => "归档",
+            TaskTransition::Unarchive => "取消归档",
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
 pub struct Task {
     pub id: Uuid,
     pub account_id: Uuid,
     pub title: String,
     pub description: Option<String>,
     #[sqlx(rename = "type")]
-    pub task_type: String,
-    pub subject: String,
-    pub status: String,
+    pub task_type: TaskType,
+    pub subject: Subject,
+    pub status: TaskStatus,
     pub due_date: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
-    pub source: String,
+    pub auto_complete: bool,
+    pub source: TaskSource,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[expect(dead_code)]
     pub created_by: Uuid,
+    #[expect(dead_code)]
     pub updated_by: Uuid,
+    #[expect(dead_code)]
     pub is_deleted: bool,
 }
 
@@ -26,14 +203,22 @@ impl From<Task> for TaskResponse {
     fn from(t: Task) -> Self {
         TaskResponse {
             id: t.id,
+            account_id: t.account_id,
             title: t.title,
             description: t.description,
             task_type: t.task_type,
             subject: t.subject,
             status: t.status,
+            source: t.source,
             due_date: t.due_date,
             completed_at: t.completed_at,
             created_at: t.created_at,
+            updated_at: t.updated_at,
+            auto_complete: t.auto_complete,
+            blocked: false,
+            progress: None,
+            children: None,
+            resources: None,
         }
     }
 }
@@ -41,33 +226,267 @@ impl From<Task> for TaskResponse {
 // 创建请求
 #[derive(Debug, Deserialize)]
 pub struct CreateTaskRequest {
+    pub account_id: Option<Uuid>, // 任务归属账户，家长为孩子创建时指定，默认自己
     pub title: String,
     pub description: Option<String>,
-    pub task_type: String,
-    pub subject: String,
+    pub task_type: TaskType,
+    pub subject: Subject,
     pub due_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub auto_complete: bool, // 仅对计划生效：子任务全部完成后自动完成
+}
+
+// 待入库的任务（手动创建或由草稿确认生成）
+pub struct TaskCreate {
+    pub id: Uuid,
+    pub account_id: Uuid, // 任务归属账户
+    pub title: String,
+    pub description: Option<String>,
+    pub task_type: TaskType,
+    pub subject: Subject,
+    pub due_date: Option<DateTime<Utc>>,
+    pub auto_complete: bool,
+    pub source: TaskSource,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Uuid,
 }
 
 // 响应
 #[derive(Debug, Serialize)]
 pub struct TaskResponse {
     pub id: Uuid,
+    pub account_id: Uuid,
     pub title: String,
     pub description: Option<String>,
-    pub task_type: String,
-    pub subject: String,
-    pub status: String,
+    pub task_type: TaskType,
+    pub subject: Subject,
+    pub status: TaskStatus,
+    pub source: TaskSource,
     pub due_date: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub auto_complete: bool,
+    pub blocked: bool, // 存在未完成的前置任务（depends_on）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<PlanProgress>, // 仅计划返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<TaskTreeNode>>, // ?expand=tree 时返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<Vec<ResourceResponse>>, // ?expand=resources 时返回
+}
+
+// 任务详情查询参数：GET /api/tasks/{id}?expand=tree,resources
+#[derive(Debug, Deserialize)]
+pub struct TaskDetailQuery {
+    pub expand: Option<String>, // 逗号分隔
+}
+
+impl TaskDetailQuery {
+    pub fn expands(&self, name: &str) -> bool {
+        self.expand
+            .as_deref()
+            .is_some_and(|e| e.split(',').any(|item| item.trim() == name))
+    }
+}
+
+// 完成任务：POST /api/tasks/{id}/complete?force=true 忽略未完成的前置任务
+#[derive(Debug, Deserialize)]
+pub struct CompleteTaskQuery {
+    #[serde(default)]
+    pub force: bool,
+}
+
+// 计划进度：includes 子任务（递归展开嵌套计划）中非计划任务的计数，不含已归档
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct PlanProgress {
+    pub total: i64,
+    pub done: i64,
+    pub active: i64,
+    pub overdue: i64, // active 且已过截止时间
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateTaskRequest {
     pub title: Option<String>,
     pub description: Option<String>,
-    pub task_type: Option<String>,
-    pub subject: Option<String>,
-    pub status: Option<String>,
+    pub task_type: Option<TaskType>,
+    pub subject: Option<Subject>,
     pub due_date: Option<DateTime<Utc>>,
+    pub auto_complete: Option<bool>,
+    // 状态只能通过 complete / reopen / archive / unarchive 变更，请求体带 status 时直接报错
+    #[serde(default, rename = "status", deserialize_with = "reject_status")]
+    _status: (),
+}
+
+fn reject_status<'de, D: serde::Deserializer<'de>>(_: D) -> Result<(), D::Error> {
+    Err(serde::de::Error::custom(
+        "任务状态不能直接修改，请使用 POST /api/tasks/{id}/complete、/reopen、/archive 或 /unarchive",
+    ))
+}
+
+// PATCH 请求（JSON Merge Patch）：缺省字段不修改，显式 null 清空可空字段
+#[derive(Debug, Deserialize)]
+pub struct PatchTaskRequest {
+    #[serde(default)]
+    pub title: Patch<String>,
+    #[serde(default)]
+    pub description: Patch<String>,
+    #[serde(default)]
+    pub task_type: Patch<TaskType>,
+    #[serde(default)]
+    pub subject: Patch<Subject>,
+    #[serde(default)]
+    pub due_date: Patch<DateTime<Utc>>,
+    #[serde(default)]
+    pub auto_complete: Patch<bool>,
+    #[serde(default, rename = "status", deserialize_with = "reject_status")]
+    _status: (),
+}
+
+impl From<UpdateTaskRequest> for PatchTaskRequest {
+    fn from(r: UpdateTaskRequest) -> Self {
+        PatchTaskRequest {
+            title: r.title.into(),
+            description: r.description.into(),
+            task_type: r.task_type.into(),
+            subject: r.subject.into(),
+            due_date: r.due_date.into(),
+            auto_complete: r.auto_complete.into(),
+            _status: (),
+        }
+    }
+}
+
+// 列表排序字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSortKey {
+    DueDate,
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl TaskSortKey {
+    pub fn column(&self) -> &'static str {
+        match self {
+            TaskSortKey::DueDate => "due_date",
+            TaskSortKey::CreatedAt => "created_at",
+            TaskSortKey::UpdatedAt => "updated_at",
+        }
+    }
+
+    pub fn value_of(&self, task: &Task) -> Option<DateTime<Utc>> {
+        match self {
+            TaskSortKey::DueDate => task.due_date,
+            TaskSortKey::CreatedAt => Some(task.created_at),
+            TaskSortKey::UpdatedAt => Some(task.updated_at),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+// 列表查询参数：GET /api/tasks?status=active&subject=math&sort=due_date&order=asc&limit=20&cursor=...
+#[derive(Debug, Deserialize)]
+pub struct TaskListQuery {
+    pub account_id: Option<Uuid>, // 家长按孩子筛选
+    pub status: Option<TaskStatus>,
+    pub task_type: Option<TaskType>,
+    pub subject: Option<Subject>,
+    pub source: Option<TaskSource>,
+    pub due_from: Option<DateTime<Utc>>,
+    pub due_to: Option<DateTime<Utc>>,
+    pub completed_from: Option<DateTime<Utc>>,
+    pub completed_to: Option<DateTime<Utc>>,
+    pub q: Option<String>, // 标题 / 描述模糊搜索
+    #[serde(default)]
+    pub sort: TaskSortKey,
+    #[serde(default)]
+    pub order: SortOrder,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+// 分页游标：排序字段和方向 + 上一页最后一条的排序值 + id
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskCursor {
+    pub sort: String,
+    pub order: SortOrder,
+    pub value: Option<DateTime<Utc>>,
+    pub id: Uuid,
+}
+
+impl TaskCursor {
+    pub fn from_task(sort: TaskSortKey, order: SortOrder, task: &Task) -> Self {
+        TaskCursor {
+            sort: sort.column().to_string(),
+            order,
+            value: sort.value_of(task),
+            id: task.id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    /// 游标与排序字段或方向不一致时视为无效
+    pub fn decode(raw: &str, sort: TaskSortKey, order: SortOrder) -> Option<Self> {
+        let json = URL_SAFE_NO_PAD.decode(raw).ok()?;
+        let cursor: TaskCursor = serde_json::from_slice(&json).ok()?;
+        (cursor.sort == sort.column() && cursor.order == order).then_some(cursor)
+    }
+}
+
+// 分页响应
+#[derive(Debug, Serialize)]
+pub struct TaskPage {
+    pub items: Vec<TaskResponse>,
+    pub next_cursor: Option<String>,
+}
+
+// 枚举字段的可选值，供前端渲染下拉框 / 校验
+#[derive(Debug, Serialize)]
+pub struct TaskOptionsResponse {
+    pub task_types: [TaskType; 5],
+    pub subjects: [Subject; 7],
+    pub statuses: [TaskStatus; 3],
+    pub sources: [TaskSource; 4],
+}
+
+impl Default for TaskOptionsResponse {
+    fn default() -> Self {
+        TaskOptionsResponse {
+            task_types: TaskType::ALL,
+            subjects: Subject::ALL,
+            statuses: TaskStatus::ALL,
+            sources: TaskSource::ALL,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_cannot_be_updated_directly() {
+        let err =
+            serde_json::from_str::<UpdateTaskRequest>(r#"{"title":"a","status":"completed"}"#)
+                .unwrap_err();
+        assert!(err.to_string().contains("/complete"));
+        assert!(serde_json::from_str::<PatchTaskRequest>(r#"{"status":"archived"}"#).is_err());
+
+        let req = serde_json::from_str::<UpdateTaskRequest>(r#"{"title":"a"}"#).unwrap();
+        assert_eq!(req.title.as_deref(), Some("a"));
+    }
 }
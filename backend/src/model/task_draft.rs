@@ -0,0 +1,128 @@
+use crate::model::patch::Patch;
+use crate::model::task::{Subject, TaskCreate, TaskSource, TaskType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// 草稿状态，对应 chk_task_draft_status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+pub enum DraftStatus {
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+impl DraftStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DraftStatus::Pending => "pending",
+            DraftStatus::Accepted => "accepted",
+            DraftStatus::Rejected => "rejected",
+        }
+    }
+}
+
+// 数据库对应的实体
+#[derive(Debug, sqlx::FromRow)]
+pub struct TaskDraft {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub resource_id: Uuid,
+    pub batch_id: Uuid,
+    pub position: i32,
+    pub title: String,
+    pub description: Option<String>,
+    pub task_type: TaskType,
+    pub subject: Subject,
+    pub due_date: Option<DateTime<Utc>>,
+    pub status: DraftStatus,
+    pub task_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Uuid,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: Uuid,
+}
+
+impl TaskDraft {
+    /// 确认时用于创建正式任务
+    pub fn to_task_create(&self, actor_id: Uuid, now: DateTime<Utc>) -> TaskCreate {
+        TaskCreate {
+            id: Uuid::new_v4(),
+            account_id: self.account_id,
+            title: self.title.clone(),
+            description: self.description.clone(),
+            task_type: self.task_type,
+            subject: self.subject,
+            due_date: self.due_date,
+            auto_complete: false,
+            source: TaskSource::AiExtract,
+            created_at: now,
+            created_by: actor_id,
+        }
+    }
+}
+
+// 列表查询参数：GET /api/task-drafts?resource_id=...&status=pending
+#[derive(Debug, Deserialize)]
+pub struct TaskDraftListQuery {
+    pub resource_id: Option<Uuid>,
+    pub status: Option<DraftStatus>, // 默认 pending
+}
+
+// 编辑草稿（JSON Merge Patch），仅待确认的草稿可编辑
+#[derive(Debug, Deserialize)]
+pub struct PatchTaskDraftRequest {
+    #[serde(default)]
+    pub title: Patch<String>,
+    #[serde(default)]
+    pub description: Patch<String>,
+    #[serde(default)]
+    pub task_type: Patch<TaskType>,
+    #[serde(default)]
+    pub subject: Patch<Subject>,
+    #[serde(default)]
+    pub due_date: Patch<DateTime<Utc>>,
+}
+
+// 批量确认 / 拒绝
+#[derive(Debug, Deserialize)]
+pub struct TaskDraftIdsRequest {
+    pub draft_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskDraftResponse {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub resource_id: Uuid,
+    pub batch_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub task_type: TaskType,
+    pub subject: Subject,
+    pub due_date: Option<DateTime<Utc>>,
+    pub status: DraftStatus,
+    pub task_id: Option<Uuid>, // 已确认时为生成的任务
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<TaskDraft> for TaskDraftResponse {
+    fn from(d: TaskDraft) -> Self {
+        TaskDraftResponse {
+            id: d.id,
+            account_id: d.account_id,
+            resource_id: d.resource_id,
+            batch_id: d.batch_id,
+            title: d.title,
+            description: d.description,
+            task_type: d.task_type,
+            subject: d.subject,
+            due_date: d.due_date,
+            status: d.status,
+            task_id: d.task_id,
+            created_at: d.created_at,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::model::task::{Task, TaskResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// 关联类型，对应 chk_task_link_type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+pub enum LinkType {
+    Includes,  // 计划包含任务
+    AiSplit,   // AI 拆分出的子任务
+    DependsOn, // parent 依赖 child（child 为前置任务）
+}
+
+impl LinkType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkType::Includes => "includes",
+            LinkType::AiSplit => "ai_split",
+            LinkType::DependsOn => "depends_on",
+        }
+    }
+
+    /// 同一张图中的关联类型：includes / ai_split 构成层级，depends_on 单独构成依赖图，
+    /// 环检测只在同一张图内进行
+    pub fn graph(&self) -> &'static [&'static str] {
+        match self {
+            LinkType::Includes | LinkType::AiSplit => &["includes", "ai_split"],
+            LinkType::DependsOn => &["depends_on"],
+        }
+    }
+}
+
+// 创建关联：当前任务为 parent
+#[derive(Debug, Deserialize)]
+pub struct CreateTaskLinkRequest {
+    pub child_task_id: Uuid,
+    pub link_type: LinkType,
+}
+
+// 列表 / 删除时按类型过滤
+#[derive(Debug, Deserialize)]
+pub struct TaskLinkQuery {
+    pub link_type: Option<LinkType>,
+}
+
+// 关联的另一端任务
+#[derive(Debug, sqlx::FromRow)]
+pub struct LinkedTask {
+    pub link_type: LinkType,
+    pub linked_at: DateTime<Utc>,
+    #[sqlx(flatten)]
+    pub task: Task,
+}
+
+// 任务树中的一条边：parent -> task
+#[derive(Debug, sqlx::FromRow)]
+pub struct TaskTreeEdge {
+    pub parent_task_id: Uuid,
+    pub link_type: LinkType,
+    #[sqlx(flatten)]
+    pub task: Task,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LinkedTaskResponse {
+    pub link_type: LinkType,
+    pub linked_at: DateTime<Utc>,
+    pub task: TaskResponse,
+}
+
+// 展开的任务树节点，子节点挂在 task.children 上
+#[derive(Debug, Serialize)]
+pub struct TaskTreeNode {
+    pub link_type: LinkType,
+    pub task: TaskResponse,
+}
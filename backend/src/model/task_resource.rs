@@ -0,0 +1,48 @@
+use crate::model::resource::{Resource, ResourceResponse};
+use crate::model::task::{Task, TaskResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// 给任务关联资源
+#[derive(Debug, Deserialize)]
+pub struct AttachResourceRequest {
+    pub resource_id: Uuid,
+}
+
+// 任务关联的资源
+#[derive(Debug, sqlx::FromRow)]
+pub struct LinkedResource {
+    pub linked_at: DateTime<Utc>,
+    #[sqlx(flatten)]
+    pub resource: Resource,
+}
+
+// 使用该资源的任务
+#[derive(Debug, sqlx::FromRow)]
+pub struct ResourceTask {
+    pub linked_at: DateTime<Utc>,
+    #[sqlx(flatten)]
+    pub task: Task,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LinkedResourceResponse {
+    pub linked_at: DateTime<Utc>,
+    pub resource: ResourceResponse,
+}
+
+impl From<LinkedResource> for LinkedResourceResponse {
+    fn from(l: LinkedResource) -> Self {
+        LinkedResourceResponse {
+            linked_at: l.linked_at,
+            resource: l.resource.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResourceTaskResponse {
+    pub linked_at: DateTime<Utc>,
+    pub task: TaskResponse,
+}
@@ -0,0 +1,41 @@
+use crate::error::AppError;
+use crate::ocr::OcrEngine;
+use salvo::async_trait;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const DEFAULT_TEXT: &str = "数学作业：练习册第1题、第2题、第3题\n预习英语第三单元";
+
+/// 固定输出的 OCR，不依赖 tesseract，结果与输入无关
+pub struct FakeOcr {
+    text: String,
+    calls: AtomicUsize, // 识别次数，测试中用于确认缓存生效
+}
+
+impl FakeOcr {
+    /// 指定识别结果，用于测试识别后的流程
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for FakeOcr {
+    fn default() -> Self {
+        Self::new(DEFAULT_TEXT)
+    }
+}
+
+#[async_trait]
+impl OcrEngine for FakeOcr {
+    async fn recognize(&self, _image: &[u8]) -> Result<String, AppError> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        Ok(self.text.clone())
+    }
+}
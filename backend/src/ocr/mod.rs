@@ -0,0 +1,88 @@
+use crate::config::{AppConfig, OcrBackend};
+use crate::error::AppError;
+use salvo::async_trait;
+use std::process::{Output, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+pub mod fake;
+pub mod pdf;
+pub mod tesseract;
+
+// 保存的文字上限（字符），避免超长文档撑爆 LLM 上下文
+const MAX_TEXT_CHARS: usize = 20_000;
+
+/// 图片文字识别，返回识别出的原始文字（可能为空）
+#[async_trait]
+pub trait OcrEngine: Send + Sync {
+    async fn recognize(&self, image: &[u8]) -> Result<String, AppError>;
+}
+
+/// 按配置创建 OCR 引擎，未启用时返回 None
+pub fn create_ocr_engine(config: &AppConfig) -> Option<Arc<dyn OcrEngine>> {
+    match config.ocr.backend {
+        OcrBackend::Disabled => None,
+        OcrBackend::Fake => Some(Arc::new(fake::FakeOcr::default())),
+        OcrBackend::Tesseract => Some(Arc::new(tesseract::TesseractOcr::new(&config.ocr))),
+    }
+}
+
+/// 文本清洗：去掉分页符和行尾空白，合并连续空行，超长截断
+pub fn clean_text(raw: &str) -> String {
+    let mut text = String::new();
+    let mut blank = false;
+    for line in raw.replace('\u{c}', "\n").lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            blank = !text.is_empty();
+            continue;
+        }
+        if blank {
+            text.push('\n');
+            blank = false;
+        }
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(line);
+    }
+    match text.char_indices().nth(MAX_TEXT_CHARS) {
+        Some((end, _)) => text[..end].to_string(),
+        None => text,
+    }
+}
+
+/// 运行外部命令：输入从 stdin 写入，读取 stdout；命令不存在时返回 NotFound 由调用方决定如何处理
+async fn run_piped(
+    program: &str,
+    args: &[&str],
+    input: &[u8],
+    timeout: Duration,
+) -> Result<Output, std::io::Error> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    // 边写 stdin 边读 stdout，避免管道缓冲区写满互相等待
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| std::io::Error::other("stdin 不可用"))?;
+    let input = input.to_vec();
+    let writer = tokio::spawn(async move {
+        // 命令可能只读取部分内容就退出，写入失败不影响结果
+        let _ = stdin.write_all(&input).await;
+    });
+
+    let output = tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "执行超时"))??;
+    writer.abort();
+    Ok(output)
+}
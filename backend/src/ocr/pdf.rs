@@ -0,0 +1,32 @@
+use crate::error::AppError;
+use crate::ocr::run_piped;
+use std::time::Duration;
+
+// 外部命令超时，避免异常 PDF 卡住后台任务
+const EXTRACT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 用 pdftotext 提取 PDF 内嵌的文字（扫描件通常为空）；命令不存在时返回 None
+pub async fn extract_text(command: &str, data: &[u8]) -> Result<Option<String>, AppError> {
+    let output = match run_piped(command, &["-enc", "UTF-8", "-", "-"], data, EXTRACT_TIMEOUT).await
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::debug!(command, "未找到 PDF 文字提取命令，跳过");
+            return Ok(None);
+        }
+        Err(e) => {
+            tracing::error!(err = ?e, command, "执行 PDF 文字提取失败");
+            return Err(AppError::Internal);
+        }
+    };
+
+    if !output.status.success() {
+        tracing::warn!(
+            status = ?output.status,
+            stderr = %String::from_utf8_lossy(&output.stderr),
+            "PDF 文字提取失败"
+        );
+        return Err(AppError::Internal);
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+}
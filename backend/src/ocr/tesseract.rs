@@ -0,0 +1,45 @@
+use crate::config::OcrConfig;
+use crate::error::AppError;
+use crate::ocr::{run_piped, OcrEngine};
+use salvo::async_trait;
+use std::time::Duration;
+
+/// 调用本机 tesseract 命令识别，图片从 stdin 读入，文字输出到 stdout
+pub struct TesseractOcr {
+    command: String,
+    languages: String,
+    timeout: Duration,
+}
+
+impl TesseractOcr {
+    pub fn new(config: &OcrConfig) -> Self {
+        Self {
+            command: config.command.clone(),
+            languages: config.languages.clone(),
+            timeout: Duration::from_secs(config.timeout_secs),
+        }
+    }
+}
+
+#[async_trait]
+impl OcrEngine for TesseractOcr {
+    async fn recognize(&self, image: &[u8]) -> Result<String, AppError> {
+        let args = ["stdin", "stdout", "-l", &self.languages];
+        let output = run_piped(&self.command, &args, image, self.timeout)
+            .await
+            .map_err(|e| {
+                tracing::error!(err = ?e, command = %self.command, "执行 OCR 命令失败");
+                AppError::Internal
+            })?;
+
+        if !output.status.success() {
+            tracing::warn!(
+                status = ?output.status,
+                stderr = %String::from_utf8_lossy(&output.stderr),
+                "OCR 识别失败"
+            );
+            return Err(AppError::Internal);
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
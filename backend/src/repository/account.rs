@@ -1,7 +1,9 @@
 use crate::app::from_pool::FromPool;
 use crate::error::AppError;
 use crate::model::account::{Account, AccountCreate};
-use sqlx::PgPool;
+use crate::model::family::FamilyCreate;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct AccountRepository {
@@ -20,27 +22,71 @@ impl AccountRepository {
     }
 
     pub async fn insert(&self, account: AccountCreate) -> Result<(), AppError> {
+        Self::insert_account(&self.pool, account).await
+    }
+
+    /// 注册家长：同一事务内创建家庭和账户
+    pub async fn insert_with_family(
+        &self,
+        family: FamilyCreate,
+        account: AccountCreate,
+    ) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
         sqlx::query!(
-            r#"INSERT INTO account(id, username, password_hash, nickname, role, created_at, created_by, updated_at, updated_by, is_deleted)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
+            r#"INSERT INTO family(id, name, created_at, created_by, updated_at, updated_by, is_deleted)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+            family.id,
+            family.name,
+            family.created_at,
+            family.created_by,
+            family.created_at,
+            family.created_by,
+            false,
+        )
+        .execute(&mut *tx)
+        .await?;
+        Self::insert_account(&mut *tx, account).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn insert_account<'e, E: PgExecutor<'e>>(
+        executor: E,
+        account: AccountCreate,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"INSERT INTO account(id, username, password_hash, nickname, role, family_id, created_at, created_by, updated_at, updated_by, is_deleted)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"#,
             account.id,
             account.username,
             account.password_hash,
             account.nickname,
             account.role,
+            account.family_id,
             account.created_at,
             account.created_by,
             account.updated_at,
             account.updated_by,
             account.is_deleted,
-        ).execute(&self.pool).await?;
+        )
+        .execute(executor)
+        .await
+        .map_err(|err| match err {
+            // 并发注册同名账户时由唯一索引兜底
+            sqlx::Error::Database(ref db_err)
+                if db_err.constraint() == Some("idx_account_username") =>
+            {
+                AppError::BadRequest("用户名已存在".into())
+            }
+            _ => err.into(),
+        })?;
         Ok(())
     }
 
     pub async fn get_by_username(&self, username: &str) -> Result<Account, AppError> {
         let account = sqlx::query_as!(
             Account,
-            r#"select * from account where username = $1"#,
+            r#"select * from account where username = $1 and is_deleted = false"#,
             username
         )
         .fetch_one(&self.pool)
@@ -48,4 +94,102 @@ impl AccountRepository {
 
         Ok(account)
     }
+
+    pub async fn get_by_id(&self, account_id: Uuid) -> Result<Account, AppError> {
+        let account = sqlx::query_as!(
+            Account,
+            r#"select * from account where id = $1 and is_deleted = false"#,
+            account_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(account)
+    }
+
+    /// 用户名是否已被未删除的账户占用，已删除账户的用户名可以再次使用
+    pub async fn exists_username(&self, username: &str) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar!(
+            r#"select exists(select 1 from account where username = $1 and is_deleted = false) as "exists!""#,
+            username
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    pub async fn find_by_family(&self, family_id: Uuid) -> Result<Vec<Account>, AppError> {
+        let accounts = sqlx::query_as!(
+            Account,
+            r#"select * from account where family_id = $1 and is_deleted = false order by created_at"#,
+            family_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(accounts)
+    }
+
+    /// 当前账户可访问的账户 ID：家长可访问整个家庭，孩子只能访问自己
+    pub async fn visible_account_ids(&self, account_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let ids = sqlx::query_scalar!(
+            r#"
+                SELECT a.id FROM account a
+                JOIN account me ON me.id = $1 AND me.is_deleted = false
+                WHERE a.is_deleted = false
+                  AND (a.id = me.id OR (me.role = 'parent' AND a.family_id = me.family_id))
+            "#,
+            account_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    pub async fn update_profile(
+        &self,
+        account_id: Uuid,
+        nickname: Option<String>,
+        password_hash: Option<String>,
+        updated_by: Uuid,
+    ) -> Result<(), AppError> {
+        let now = chrono::Utc::now();
+        sqlx::query!(
+            r#"
+                UPDATE account SET
+                  nickname = COALESCE($1, nickname),
+                  password_hash = COALESCE($2, password_hash),
+                  updated_at = $3,
+                  updated_by = $4
+                WHERE id = $5 AND is_deleted = false
+            "#,
+            nickname,
+            password_hash,
+            now,
+            updated_by,
+            account_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, account_id: Uuid, deleted_by: Uuid) -> Result<(), AppError> {
+        let now = chrono::Utc::now();
+        sqlx::query!(
+            r#"
+                UPDATE account SET is_deleted = true, updated_at = $1, updated_by = $2 WHERE id = $3
+            "#,
+            now,
+            deleted_by,
+            account_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
 }
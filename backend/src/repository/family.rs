@@ -0,0 +1,34 @@
+use crate::app::from_pool::FromPool;
+use crate::error::AppError;
+use crate::model::family::Family;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct FamilyRepository {
+    pool: PgPool,
+}
+
+impl FromPool for FamilyRepository {
+    fn from_pool(pool: PgPool) -> Self {
+        Self::new(pool)
+    }
+}
+
+impl FamilyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_by_id(&self, family_id: Uuid) -> Result<Family, AppError> {
+        let family = sqlx::query_as!(
+            Family,
+            r#"SELECT * FROM family WHERE id = $1 AND is_deleted = false"#,
+            family_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(family)
+    }
+}
@@ -0,0 +1,258 @@
+use crate::app::from_pool::FromPool;
+use crate::error::AppError;
+use crate::model::job::{Job, JobPayload, JobStatus};
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct JobRepository {
+    pool: PgPool,
+}
+
+impl FromPool for JobRepository {
+    fn from_pool(pool: PgPool) -> Self {
+        Self::new(pool)
+    }
+}
+
+impl JobRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn enqueue(
+        &self,
+        payload: &JobPayload,
+        account_id: Option<Uuid>,
+    ) -> Result<Job, AppError> {
+        let payload_json = serde_json::to_value(payload).map_err(|e| {
+            tracing::error!(err = ?e, "序列化任务参数失败");
+            AppError::Internal
+        })?;
+        let now = chrono::Utc::now();
+
+        let job = sqlx::query_as!(
+            Job,
+            r#"INSERT INTO job(id, kind, payload, status, attempts, max_attempts, run_at, account_id, created_at, updated_at)
+               VALUES ($1, $2, $3, $4, 0, $5, $6, $7, $6, $6)
+               RETURNING id, kind, payload, status AS "status: JobStatus", attempts, max_attempts,
+                         run_at, locked_at, locked_by, last_error, result, account_id,
+                         created_at, updated_at, finished_at"#,
+            Uuid::new_v4(),
+            payload.kind(),
+            payload_json,
+            JobStatus::Queued.as_str(),
+            payload.max_attempts(),
+            now,
+            account_id,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    /// 抢占一个到期的任务：SKIP LOCKED 让多个 worker 并发取任务时互不阻塞
+    pub async fn claim(&self, worker_id: &str) -> Result<Option<Job>, AppError> {
+        let job = sqlx::query_as!(
+            Job,
+            r#"
+                UPDATE job SET status = $1, attempts = attempts + 1,
+                    locked_at = now(), locked_by = $2, updated_at = now()
+                WHERE id = (
+                    SELECT id FROM job
+                    WHERE status = $3 AND run_at <= now()
+                    ORDER BY run_at
+                    FOR UPDATE SKIP LOCKED
+                    LIMIT 1
+                )
+                RETURNING id, kind, payload, status AS "status: JobStatus", attempts, max_attempts,
+                          run_at, locked_at, locked_by, last_error, result, account_id,
+                          created_at, updated_at, finished_at
+            "#,
+            JobStatus::Running.as_str(),
+            worker_id,
+            JobStatus::Queued.as_str(),
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    /// 记录执行成功；只有仍持有该任务的 worker 能写入，任务已被回收时返回 409
+    pub async fn succeed(
+        &self,
+        job_id: Uuid,
+        worker_id: &str,
+        result: Option<Value>,
+    ) -> Result<(), AppError> {
+        let updated = sqlx::query!(
+            r#"
+                UPDATE job SET status = $2, result = $3, last_error = NULL,
+                    locked_at = NULL, locked_by = NULL, updated_at = now(), finished_at = now()
+                WHERE id = $1 AND locked_by = $4 AND status = $5
+            "#,
+            job_id,
+            JobStatus::Succeeded.as_str(),
+            result,
+            worker_id,
+            JobStatus::Running.as_str(),
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if updated.rows_affected() == 0 {
+            return Err(AppError::Conflict(
+                "任务已被回收或由其它 worker 接管".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 执行失败：retry_at 为空时进入死信，否则重新排队到 retry_at；与 succeed 相同只接受持有者写入
+    pub async fn fail(
+        &self,
+        job_id: Uuid,
+        worker_id: &str,
+        error: &str,
+        retry_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<(), AppError> {
+        let status = match retry_at {
+            Some(_) => JobStatus::Queued,
+            None => JobStatus::Dead,
+        };
+        let updated = sqlx::query!(
+            r#"
+                UPDATE job SET status = $2, last_error = $3, run_at = COALESCE($4, run_at),
+                    locked_at = NULL, locked_by = NULL, updated_at = now(),
+                    finished_at = CASE WHEN $4::timestamptz IS NULL THEN now() END
+                WHERE id = $1 AND locked_by = $5 AND status = $6
+            "#,
+            job_id,
+            status.as_str(),
+            error,
+            retry_at,
+            worker_id,
+            JobStatus::Running.as_str(),
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if updated.rows_affected() == 0 {
+            return Err(AppError::Conflict(
+                "任务已被回收或由其它 worker 接管".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 执行超时仍为 running 的任务（worker 崩溃或被强制停止）重新排队，
+    /// 次数已用完的直接进入死信；返回 (重新排队数, 进入死信数)
+    pub async fn requeue_stale(
+        &self,
+        locked_before: chrono::DateTime<chrono::Utc>,
+        error: &str,
+    ) -> Result<(u64, u64), AppError> {
+        let statuses = sqlx::query_scalar!(
+            r#"
+                UPDATE job SET
+                    status = CASE WHEN attempts >= max_attempts THEN $1 ELSE $2 END,
+                    last_error = $3,
+                    finished_at = CASE WHEN attempts >= max_attempts THEN now() END,
+                    locked_at = NULL, locked_by = NULL, updated_at = now()
+                WHERE status = $4 AND locked_at < $5
+                RETURNING status AS "status: JobStatus"
+            "#,
+            JobStatus::Dead.as_str(),
+            JobStatus::Queued.as_str(),
+            error,
+            JobStatus::Running.as_str(),
+            locked_before,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let dead = statuses.iter().filter(|s| **s == JobStatus::Dead).count() as u64;
+        Ok((statuses.len() as u64 - dead, dead))
+    }
+
+    /// 死信任务重新入队，重置执行次数并清除上次的错误
+    pub async fn revive(&self, job_id: Uuid, account_ids: &[Uuid]) -> Result<Job, AppError> {
+        let job = sqlx::query_as!(
+            Job,
+            r#"
+                UPDATE job SET status = $1, attempts = 0, run_at = now(), last_error = NULL,
+                    updated_at = now(), finished_at = NULL
+                WHERE id = $2 AND account_id = ANY($3) AND status = $4
+                RETURNING id, kind, payload, status AS "status: JobStatus", attempts, max_attempts,
+                          run_at, locked_at, locked_by, last_error, result, account_id,
+                          created_at, updated_at, finished_at
+            "#,
+            JobStatus::Queued.as_str(),
+            job_id,
+            account_ids,
+            JobStatus::Dead.as_str(),
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    pub async fn find_by_id_and_account(
+        &self,
+        job_id: Uuid,
+        account_ids: &[Uuid],
+    ) -> Result<Job, AppError> {
+        let job = sqlx::query_as!(
+            Job,
+            r#"
+                SELECT id, kind, payload, status AS "status: JobStatus", attempts, max_attempts,
+                    run_at, locked_at, locked_by, last_error, result, account_id,
+                    created_at, updated_at, finished_at
+                FROM job
+                WHERE id = $1 AND account_id = ANY($2)
+            "#,
+            job_id,
+            account_ids
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    /// 最近的任务，按创建时间倒序
+    pub async fn find(
+        &self,
+        account_ids: &[Uuid],
+        status: Option<JobStatus>,
+        kind: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Job>, AppError> {
+        let jobs = sqlx::query_as!(
+            Job,
+            r#"
+                SELECT id, kind, payload, status AS "status: JobStatus", attempts, max_attempts,
+                    run_at, locked_at, locked_by, last_error, result, account_id,
+                    created_at, updated_at, finished_at
+                FROM job
+                WHERE account_id = ANY($1)
+                  AND ($2::text IS NULL OR status = $2)
+                  AND ($3::text IS NULL OR kind = $3)
+                ORDER BY created_at DESC, id DESC
+                LIMIT $4
+            "#,
+            account_ids,
+            status.map(|s| s.as_str()),
+            kind,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(jobs)
+    }
+}
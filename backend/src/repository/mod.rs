@@ -1,2 +1,17 @@
 pub mod account;
+pub mod family;
+pub mod job;
+pub mod resource;
+pub mod session;
 pub mod task;
+pub mod task_draft;
+pub mod task_link;
+pub mod task_resource;
+
+// 转义 LIKE 通配符，用户输入按字面匹配
+pub(crate) fn escape_like(input: &str) -> String {
+    input
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
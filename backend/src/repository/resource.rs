@@ -0,0 +1,383 @@
+use crate::app::from_pool::FromPool;
+use crate::error::AppError;
+use crate::model::resource::{FileType, Resource, ResourceCreate, ResourceCursor};
+use crate::repository::escape_like;
+use sqlx::{PgExecutor, PgPool, Postgres, QueryBuilder};
+use std::future::Future;
+use uuid::Uuid;
+
+// 动态查询使用的列
+const RESOURCE_COLUMNS: [&str; 15] = [
+    "id",
+    "account_id",
+    "file_name",
+    "file_type",
+    "storage_url",
+    "content_hash",
+    "file_size",
+    "mime_type",
+    "extracted_text",
+    "thumbnail_key",
+    "created_at",
+    "created_by",
+    "updated_at",
+    "updated_by",
+    "is_deleted",
+];
+
+/// 动态 SQL 的 resource 列清单，联表查询时传入表别名（如 "r"）
+pub(crate) fn resource_columns(alias: Option<&str>) -> String {
+    RESOURCE_COLUMNS
+        .iter()
+        .map(|c| match alias {
+            Some(a) => format!("{a}.{c}"),
+            None => c.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Clone)]
+pub struct ResourceRepository {
+    pool: PgPool,
+}
+
+impl FromPool for ResourceRepository {
+    fn from_pool(pool: PgPool) -> Self {
+        Self::new(pool)
+    }
+}
+
+impl ResourceRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 入库并校验配额：同一账户内按内容去重计算已用空间，已存在相同内容时不重复计入
+    ///
+    /// 配额检查与插入在同一事务内，并按账户加事务级 advisory lock，避免并发上传超出配额
+    pub async fn insert(
+        &self,
+        input: ResourceCreate,
+        quota_bytes: i64,
+    ) -> Result<Resource, AppError> {
+        let mut tx = self.pool.begin().await?;
+        // pg_advisory_xact_lock 返回 void，query! 无法推断列类型，这里用运行时查询
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('resource:' || $1::text))")
+            .bind(input.account_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let used = Self::usage_of(&mut *tx, input.account_id).await?;
+        let duplicated = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM resource
+                    WHERE account_id = $1 AND content_hash = $2 AND is_deleted = false
+                ) AS "exists!"
+            "#,
+            input.account_id,
+            input.content_hash
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if !duplicated && used + input.file_size > quota_bytes {
+            return Err(AppError::QuotaExceeded {
+                used,
+                requested: input.file_size,
+                quota: quota_bytes,
+            });
+        }
+
+        let resource = sqlx::query_as!(
+            Resource,
+            r#"INSERT INTO resource(id, account_id, file_name, file_type, storage_url, content_hash, file_size, mime_type, extracted_text, thumbnail_key, created_at, created_by, updated_at, updated_by, is_deleted)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NULL, NULL, $9, $10, $11, $12, $13)
+               RETURNING id, account_id, file_name,
+                         file_type AS "file_type: FileType",
+                         storage_url, content_hash, file_size, mime_type, extracted_text, thumbnail_key,
+                         created_at, created_by, updated_at, updated_by, is_deleted"#,
+            input.id,
+            input.account_id,
+            input.file_name,
+            input.file_type.as_str(),
+            input.storage_url,
+            input.content_hash,
+            input.file_size,
+            input.mime_type,
+            input.created_at,
+            input.created_by,
+            input.created_at,
+            input.created_by,
+            false,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(resource)
+    }
+
+    /// 账户下相同内容已存储对象的地址（最后一个引用删除时对象随之清理，只看未删除的资源）
+    pub async fn find_blob_url(
+        &self,
+        account_id: Uuid,
+        content_hash: &str,
+    ) -> Result<Option<String>, AppError> {
+        let url = sqlx::query_scalar!(
+            r#"
+                SELECT storage_url FROM resource
+                WHERE account_id = $1 AND content_hash = $2 AND is_deleted = false
+                LIMIT 1
+            "#,
+            account_id,
+            content_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(url)
+    }
+
+    /// 账户已用空间（字节），相同内容只计一次。
+    ///
+    /// 0007 之前上传的资源没有记录大小（file_size 为 0），不计入已用空间
+    pub async fn usage(&self, account_id: Uuid) -> Result<i64, AppError> {
+        Self::usage_of(&self.pool, account_id).await
+    }
+
+    async fn usage_of<'e, E: PgExecutor<'e>>(
+        executor: E,
+        account_id: Uuid,
+    ) -> Result<i64, AppError> {
+        let used = sqlx::query_scalar!(
+            r#"
+                SELECT COALESCE(SUM(file_size), 0)::BIGINT AS "used!"
+                FROM (
+                    SELECT DISTINCT ON (COALESCE(content_hash, id::text)) file_size
+                    FROM resource
+                    WHERE account_id = $1 AND is_deleted = false
+                    ORDER BY COALESCE(content_hash, id::text)
+                ) blobs
+            "#,
+            account_id
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(used)
+    }
+
+    pub async fn find_by_id_and_account(
+        &self,
+        resource_id: Uuid,
+        account_ids: &[Uuid],
+    ) -> Result<Resource, AppError> {
+        let resource = sqlx::query_as!(
+            Resource,
+            r#"
+                SELECT id, account_id, file_name,
+                    file_type AS "file_type: FileType",
+                    storage_url, content_hash, file_size, mime_type, extracted_text, thumbnail_key,
+                    created_at, created_by, updated_at, updated_by, is_deleted
+                FROM resource
+                WHERE id = $1 AND account_id = ANY($2) AND is_deleted = false
+            "#,
+            resource_id,
+            account_ids
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(resource)
+    }
+
+    /// 按 ID 查询，不校验归属（签名下载链接已在签发时校验）
+    pub async fn find_by_id(&self, resource_id: Uuid) -> Result<Resource, AppError> {
+        let resource = sqlx::query_as!(
+            Resource,
+            r#"
+                SELECT id, account_id, file_name,
+                    file_type AS "file_type: FileType",
+                    storage_url, content_hash, file_size, mime_type, extracted_text, thumbnail_key,
+                    created_at, created_by, updated_at, updated_by, is_deleted
+                FROM resource
+                WHERE id = $1 AND is_deleted = false
+            "#,
+            resource_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(resource)
+    }
+
+    /// 记录后台生成的缩略图
+    pub async fn set_thumbnail(
+        &self,
+        resource_id: Uuid,
+        thumbnail_key: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE resource SET thumbnail_key = $2 WHERE id = $1",
+            resource_id,
+            thumbnail_key
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 缓存提取的文字，空字符串表示已提取但没有文字
+    pub async fn set_extracted_text(&self, resource_id: Uuid, text: &str) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE resource SET extracted_text = $2 WHERE id = $1",
+            resource_id,
+            text
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 账户下相同内容的资源已提取过的文字
+    pub async fn find_extracted_text(
+        &self,
+        account_id: Uuid,
+        content_hash: &str,
+    ) -> Result<Option<String>, AppError> {
+        let text = sqlx::query_scalar!(
+            r#"
+                SELECT extracted_text AS "extracted_text!" FROM resource
+                WHERE account_id = $1 AND content_hash = $2 AND extracted_text IS NOT NULL
+                LIMIT 1
+            "#,
+            account_id,
+            content_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(text)
+    }
+
+    /// 条件查询 + keyset 分页（created_at 倒序），多取一条用于判断是否还有下一页
+    pub async fn search(
+        &self,
+        account_ids: &[Uuid],
+        file_type: Option<FileType>,
+        q: Option<&str>,
+        cursor: Option<&ResourceCursor>,
+        limit: i64,
+    ) -> Result<Vec<Resource>, AppError> {
+        let mut qb = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {} FROM resource WHERE is_deleted = false AND account_id = ANY(",
+            resource_columns(None)
+        ));
+        qb.push_bind(account_ids.to_vec()).push(")");
+
+        if let Some(file_type) = file_type {
+            qb.push(" AND file_type = ").push_bind(file_type.as_str());
+        }
+        if let Some(q) = q.map(str::trim).filter(|q| !q.is_empty()) {
+            qb.push(" AND file_name ILIKE ")
+                .push_bind(format!("%{}%", escape_like(q)));
+        }
+        if let Some(cursor) = cursor {
+            qb.push(" AND (created_at, id) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+
+        qb.push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(limit + 1);
+
+        let resources = qb
+            .build_query_as::<Resource>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(resources)
+    }
+
+    /// 软删除资源；账户内已没有其它未删除资源引用同一内容时调用 release 清理存储对象。
+    ///
+    /// release 在事务内、持有与 insert 相同的账户 advisory lock 时执行，清理期间并发上传相同内容会等待，
+    /// 入库后由上传方发现对象缺失并重新写入。不能先提交再清理：对象 key 只由账户和内容决定，
+    /// 提交后锁已释放，并发上传看不到引用会重新写入同一个 key 并入库，随后的清理会删掉它。
+    /// 代价是清理期间同一账户的上传被阻塞；清理后提交失败时资源仍在而对象已删除，下载返回 404
+    pub async fn delete<F, Fut>(
+        &self,
+        resource_id: Uuid,
+        account_ids: &[Uuid],
+        actor_id: Uuid,
+        release: F,
+    ) -> Result<(), AppError>
+    where
+        F: FnOnce(Resource) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let mut tx = self.pool.begin().await?;
+        let account_id = sqlx::query_scalar!(
+            r#"
+                SELECT account_id FROM resource
+                WHERE id = $1 AND account_id = ANY($2) AND is_deleted = false
+            "#,
+            resource_id,
+            account_ids
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('resource:' || $1::text))")
+            .bind(account_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let now = chrono::Utc::now();
+        let resource = sqlx::query_as!(
+            Resource,
+            r#"
+                UPDATE resource SET is_deleted = true, updated_at = $1, updated_by = $2
+                WHERE id = $3 AND is_deleted = false
+                RETURNING id, account_id, file_name,
+                          file_type AS "file_type: FileType",
+                          storage_url, content_hash, file_size, mime_type, extracted_text, thumbnail_key,
+                          created_at, created_by, updated_at, updated_by, is_deleted
+            "#,
+            now,
+            actor_id,
+            resource_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // 历史数据没有 content_hash，对象按资源单独存储
+        let referenced = match &resource.content_hash {
+            Some(hash) => {
+                sqlx::query_scalar!(
+                    r#"
+                        SELECT EXISTS(
+                            SELECT 1 FROM resource
+                            WHERE account_id = $1 AND content_hash = $2 AND is_deleted = false
+                        ) AS "exists!"
+                    "#,
+                    resource.account_id,
+                    hash
+                )
+                .fetch_one(&mut *tx)
+                .await?
+            }
+            None => false,
+        };
+        if !referenced {
+            release(resource).await;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use crate::app::from_pool::FromPool;
+use crate::error::AppError;
+use crate::model::session::{AccountSession, RefreshToken};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct SessionRepository {
+    pool: PgPool,
+}
+
+impl FromPool for SessionRepository {
+    fn from_pool(pool: PgPool) -> Self {
+        Self::new(pool)
+    }
+}
+
+impl SessionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn insert_session(
+        &self,
+        session_id: Uuid,
+        account_id: Uuid,
+        expires_at: &DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"INSERT INTO account_session(id, account_id, expires_at, revoked_at, created_at, created_by, updated_at, updated_by)
+               VALUES ($1, $2, $3, NULL, $4, $5, $6, $7)"#,
+            session_id,
+            account_id,
+            expires_at,
+            now,
+            account_id,
+            now,
+            account_id,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_session(&self, session_id: Uuid) -> Result<AccountSession, AppError> {
+        let session = sqlx::query_as!(
+            AccountSession,
+            r#"SELECT * FROM account_session WHERE id = $1"#,
+            session_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    /// 吊销整个会话（令牌家族），重复调用无副作用
+    pub async fn revoke_session(&self, session_id: Uuid, revoked_by: Uuid) -> Result<(), AppError> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"
+                UPDATE account_session SET revoked_at = $1, updated_at = $1, updated_by = $2
+                WHERE id = $3 AND revoked_at IS NULL
+            "#,
+            now,
+            revoked_by,
+            session_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 吊销某账户的全部会话（账户被删除时使用）
+    pub async fn revoke_account_sessions(
+        &self,
+        account_id: Uuid,
+        revoked_by: Uuid,
+    ) -> Result<(), AppError> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"
+                UPDATE account_session SET revoked_at = $1, updated_at = $1, updated_by = $2
+                WHERE account_id = $3 AND revoked_at IS NULL
+            "#,
+            now,
+            revoked_by,
+            account_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn insert_refresh_token(
+        &self,
+        session_id: Uuid,
+        token_hash: &str,
+        expires_at: &DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"INSERT INTO refresh_token(id, session_id, token_hash, expires_at, used_at, created_at)
+               VALUES ($1, $2, $3, $4, NULL, $5)"#,
+            Uuid::new_v4(),
+            session_id,
+            token_hash,
+            expires_at,
+            Utc::now(),
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_refresh_token(&self, token_hash: &str) -> Result<RefreshToken, AppError> {
+        let token = sqlx::query_as!(
+            RefreshToken,
+            r#"SELECT * FROM refresh_token WHERE token_hash = $1"#,
+            token_hash
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// 标记 refresh token 已使用；返回 false 表示已被其它请求抢先使用
+    pub async fn mark_refresh_token_used(&self, token_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query!(
+            r#"UPDATE refresh_token SET used_at = $1 WHERE id = $2 AND used_at IS NULL"#,
+            Utc::now(),
+            token_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -1,9 +1,45 @@
 use crate::app::from_pool::FromPool;
 use crate::error::AppError;
-use crate::model::task::{CreateTaskRequest, Task, UpdateTaskRequest};
-use sqlx::PgPool;
+use crate::model::task::{
+    PatchTaskRequest, SortOrder, Subject, Task, TaskCreate, TaskCursor, TaskListQuery, TaskSource,
+    TaskStatus, TaskType,
+};
+use crate::repository::escape_like;
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
+// 动态查询使用的列，type 列由 Task 上的 #[sqlx(rename = "type")] 映射
+const TASK_COLUMNS: [&str; 16] = [
+    "id",
+    "account_id",
+    "title",
+    "description",
+    "type",
+    "subject",
+    "status",
+    "due_date",
+    "completed_at",
+    "auto_complete",
+    "source",
+    "created_at",
+    "created_by",
+    "updated_at",
+    "updated_by",
+    "is_deleted",
+];
+
+/// 动态 SQL 的 task 列清单，联表查询时传入表别名（如 "t"）
+pub(crate) fn task_columns(alias: Option<&str>) -> String {
+    TASK_COLUMNS
+        .iter()
+        .map(|c| match alias {
+            Some(a) => format!("{a}.{c}"),
+            None => c.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 #[derive(Clone)]
 pub struct TaskRepository {
     pool: PgPool,
@@ -20,38 +56,45 @@ impl TaskRepository {
         Self { pool }
     }
 
-    pub async fn insert(
+    pub async fn insert(&self, input: TaskCreate) -> Result<Task, AppError> {
+        let mut conn = self.pool.acquire().await?;
+        self.insert_in(&mut conn, input).await
+    }
+
+    /// 在调用方的事务内创建任务
+    pub async fn insert_in(
         &self,
-        uid: Uuid,
-        account_id: Uuid,
-        created_at: &chrono::DateTime<chrono::Utc>,
-        updated_at: &chrono::DateTime<chrono::Utc>,
-        input: &CreateTaskRequest,
+        conn: &mut PgConnection,
+        input: TaskCreate,
     ) -> Result<Task, AppError> {
         let task = sqlx::query_as!(
             Task,
-            r#"INSERT INTO task(id, account_id, title, description, type, subject, status, due_date, completed_at, source, created_at, created_by, updated_at, updated_by, is_deleted)
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            r#"INSERT INTO task(id, account_id, title, description, type, subject, status, due_date, completed_at, source, created_at, created_by, updated_at, updated_by, is_deleted, auto_complete)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
                RETURNING id, account_id, title, description,
-                         type AS task_type,
-                         subject, status, due_date, completed_at, source,
+                         type AS "task_type: TaskType",
+                         subject AS "subject: Subject",
+                         status AS "status: TaskStatus",
+                         due_date, completed_at, auto_complete,
+                         source AS "source: TaskSource",
                          created_at, created_by, updated_at, updated_by, is_deleted"#,
-            uid,
-            account_id,
+            input.id,
+            input.account_id,
             input.title,
             input.description,
-            input.task_type,
-            input.subject,
-            "active",
+            input.task_type.as_str(),
+            input.subject.as_str(),
+            TaskStatus::Active.as_str(),
             input.due_date,
             None::<chrono::DateTime<chrono::Utc>>,
-            "manual",
-            created_at,
-            account_id,
-            updated_at,
-            account_id,
+            input.source.as_str(),
+            input.created_at,
+            input.created_by,
+            input.created_at,
+            input.created_by,
             false,
-        ).fetch_one(&self.pool).await?;
+            input.auto_complete,
+        ).fetch_one(&mut *conn).await?;
 
         Ok(task)
     }
@@ -59,20 +102,23 @@ impl TaskRepository {
     pub async fn find_by_id_and_account(
         &self,
         task_id: Uuid,
-        account_id: Uuid,
+        account_ids: &[Uuid],
     ) -> Result<Task, AppError> {
         let task = sqlx::query_as!(
             Task,
             r#"
                 SELECT id, account_id, title, description,
-                    type AS task_type,
-                    subject, status, due_date, completed_at, source,
+                    type AS "task_type: TaskType",
+                    subject AS "subject: Subject",
+                    status AS "status: TaskStatus",
+                    due_date, completed_at, auto_complete,
+                    source AS "source: TaskSource",
                     created_at, created_by, updated_at, updated_by, is_deleted
                 FROM task
-                WHERE id = $1 AND account_id = $2 AND is_deleted = false
+                WHERE id = $1 AND account_id = ANY($2) AND is_deleted = false
             "#,
             task_id,
-            account_id
+            account_ids
         )
         .fetch_one(&self.pool)
         .await?;
@@ -80,74 +126,214 @@ impl TaskRepository {
         Ok(task)
     }
 
-    pub async fn find_all_by_account(&self, account_id: Uuid) -> Result<Vec<Task>, AppError> {
-        let tasks = sqlx::query_as!(
-            Task,
-            r#"SELECT id, account_id, title, description,
-                    type AS task_type,
-                    subject, status, due_date, completed_at, source,
-                    created_at, created_by, updated_at, updated_by, is_deleted
-             FROM task
-             WHERE account_id = $1 AND is_deleted = false
-             ORDER BY created_at DESC
-        "#,
-            account_id
-        )
-        .fetch_all(&self.pool)
-        .await?;
+    /// 条件查询 + keyset 分页，多取一条用于判断是否还有下一页
+    pub async fn search(
+        &self,
+        account_ids: &[Uuid],
+        query: &TaskListQuery,
+        cursor: Option<&TaskCursor>,
+        limit: i64,
+    ) -> Result<Vec<Task>, AppError> {
+        let mut qb = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {} FROM task WHERE is_deleted = false AND account_id = ANY(",
+            task_columns(None)
+        ));
+        qb.push_bind(account_ids.to_vec()).push(")");
+
+        if let Some(status) = query.status {
+            qb.push(" AND status = ").push_bind(status.as_str());
+        }
+        if let Some(task_type) = query.task_type {
+            qb.push(" AND type = ").push_bind(task_type.as_str());
+        }
+        if let Some(subject) = query.subject {
+            qb.push(" AND subject = ").push_bind(subject.as_str());
+        }
+        if let Some(source) = query.source {
+            qb.push(" AND source = ").push_bind(source.as_str());
+        }
+        if let Some(due_from) = query.due_from {
+            qb.push(" AND due_date >= ").push_bind(due_from);
+        }
+        if let Some(due_to) = query.due_to {
+            qb.push(" AND due_date < ").push_bind(due_to);
+        }
+        if let Some(completed_from) = query.completed_from {
+            qb.push(" AND completed_at >= ").push_bind(completed_from);
+        }
+        if let Some(completed_to) = query.completed_to {
+            qb.push(" AND completed_at < ").push_bind(completed_to);
+        }
+        if let Some(q) = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            let pattern = format!("%{}%", escape_like(q));
+            qb.push(" AND (title ILIKE ")
+                .push_bind(pattern.clone())
+                .push(" OR description ILIKE ")
+                .push_bind(pattern)
+                .push(")");
+        }
+
+        let column = query.sort.column();
+        let (op, direction) = match query.order {
+            SortOrder::Asc => (">", "ASC"),
+            SortOrder::Desc => ("<", "DESC"),
+        };
+
+        // keyset 条件：排序值为 NULL 的行（仅 due_date）统一排在最后
+        if let Some(cursor) = cursor {
+            match cursor.value {
+                Some(value) => {
+                    qb.push(format!(" AND ({column} {op} "))
+                        .push_bind(value)
+                        .push(format!(" OR ({column} = "))
+                        .push_bind(value)
+                        .push(format!(" AND id {op} "))
+                        .push_bind(cursor.id)
+                        .push(format!(") OR {column} IS NULL)"));
+                }
+                None => {
+                    qb.push(format!(" AND {column} IS NULL AND id {op} "))
+                        .push_bind(cursor.id);
+                }
+            }
+        }
+
+        qb.push(format!(
+            " ORDER BY {column} {direction} NULLS LAST, id {direction} LIMIT "
+        ))
+        .push_bind(limit + 1);
+
+        let tasks = qb.build_query_as::<Task>().fetch_all(&self.pool).await?;
 
         Ok(tasks)
     }
 
-    pub async fn update(
+    /// 按 merge patch 更新：Missing 字段保持原值，Null 清空，Value 覆盖
+    pub async fn patch(
         &self,
         task_id: Uuid,
-        account_id: Uuid,
-        input: UpdateTaskRequest,
-    ) -> Result<(), AppError> {
+        account_ids: &[Uuid],
+        actor_id: Uuid,
+        input: &PatchTaskRequest,
+    ) -> Result<Task, AppError> {
         let now = chrono::Utc::now();
-        sqlx::query!(
+        let task = sqlx::query_as!(
+            Task,
             r#"
             UPDATE task SET
-              title = COALESCE($1, title),
-              description = COALESCE($2, description),
-              type = COALESCE($3, type),
-              subject = COALESCE($4, subject),
-              status = COALESCE($5, status),
-              due_date = COALESCE($6, due_date),
-              updated_at = $7,
-              updated_by = $8
-          WHERE id = $9 AND account_id = $10
+              title = CASE WHEN $1 THEN $2 ELSE title END,
+              description = CASE WHEN $3 THEN $4 ELSE description END,
+              type = CASE WHEN $5 THEN $6 ELSE type END,
+              subject = CASE WHEN $7 THEN $8 ELSE subject END,
+              due_date = CASE WHEN $9 THEN $10 ELSE due_date END,
+              auto_complete = CASE WHEN $15 THEN $16 ELSE auto_complete END,
+              updated_at = $11,
+              updated_by = $12
+            WHERE id = $13 AND account_id = ANY($14) AND is_deleted = false
+            RETURNING id, account_id, title, description,
+                type AS "task_type: TaskType",
+                subject AS "subject: Subject",
+                status AS "status: TaskStatus",
+                due_date, completed_at, auto_complete,
+                source AS "source: TaskSource",
+                created_at, created_by, updated_at, updated_by, is_deleted
         "#,
-            input.title,
-            input.description,
-            input.task_type,
-            input.subject,
-            input.status,
-            input.due_date,
+            !input.title.is_missing(),
+            input.title.value().map(String::as_str),
+            !input.description.is_missing(),
+            input.description.value().map(String::as_str),
+            !input.task_type.is_missing(),
+            input.task_type.value().map(|t| t.as_str()),
+            !input.subject.is_missing(),
+            input.subject.value().map(|s| s.as_str()),
+            !input.due_date.is_missing(),
+            input.due_date.value().copied(),
             now,
-            account_id,
+            actor_id,
             task_id,
-            account_id
+            account_ids,
+            !input.auto_complete.is_missing(),
+            input.auto_complete.value().copied(),
         )
-        .execute(&self.pool)
+        .fetch_one(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(task)
     }
 
-    pub async fn delete(&self, task_id: Uuid, account_id: Uuid) -> Result<(), AppError> {
+    /// 状态流转：仅当任务仍处于 from 状态时更新，并写入流转记录
+    pub async fn transition(
+        &self,
+        task_id: Uuid,
+        from: TaskStatus,
+        to: TaskStatus,
+        completed_at: Option<chrono::DateTime<chrono::Utc>>,
+        actor_id: Uuid,
+    ) -> Result<Task, AppError> {
         let now = chrono::Utc::now();
+        let mut tx = self.pool.begin().await?;
+
+        let task = sqlx::query_as!(
+            Task,
+            r#"
+                UPDATE task SET status = $1, completed_at = $2, updated_at = $3, updated_by = $4
+                WHERE id = $5 AND status = $6 AND is_deleted = false
+                RETURNING id, account_id, title, description,
+                    type AS "task_type: TaskType",
+                    subject AS "subject: Subject",
+                    status AS "status: TaskStatus",
+                    due_date, completed_at, auto_complete,
+                    source AS "source: TaskSource",
+                    created_at, created_by, updated_at, updated_by, is_deleted
+            "#,
+            to.as_str(),
+            completed_at,
+            now,
+            actor_id,
+            task_id,
+            from.as_str()
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("任务状态已被修改，请刷新后重试".into()))?;
+
         sqlx::query!(
+            r#"INSERT INTO task_status_log(id, task_id, from_status, to_status, created_at, created_by)
+               VALUES ($1, $2, $3, $4, $5, $6)"#,
+            Uuid::new_v4(),
+            task_id,
+            from.as_str(),
+            to.as_str(),
+            now,
+            actor_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(task)
+    }
+
+    pub async fn delete(
+        &self,
+        task_id: Uuid,
+        account_ids: &[Uuid],
+        actor_id: Uuid,
+    ) -> Result<(), AppError> {
+        let now = chrono::Utc::now();
+        let result = sqlx::query!(
             r#"
-                UPDATE task SET is_deleted = true, updated_at=$1, updated_by=$2 where id = $3 AND account_id = $4
+                UPDATE task SET is_deleted = true, updated_at=$1, updated_by=$2 where id = $3 AND account_id = ANY($4) AND is_deleted = false
             "#,
             now,
-            account_id,
+            actor_id,
             task_id,
-            account_id
+            account_ids
         ).execute(&self.pool).await?;
 
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
         Ok(())
     }
 }
@@ -0,0 +1,264 @@
+use crate::app::from_pool::FromPool;
+use crate::error::AppError;
+use crate::model::task::{Subject, TaskType};
+use crate::model::task_draft::{DraftStatus, PatchTaskDraftRequest, TaskDraft};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct TaskDraftRepository {
+    pool: PgPool,
+}
+
+impl FromPool for TaskDraftRepository {
+    fn from_pool(pool: PgPool) -> Self {
+        Self::new(pool)
+    }
+}
+
+impl TaskDraftRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 写入一批识别结果；同一资源之前未处理的草稿视为被新结果取代，标记为 rejected
+    pub async fn insert_batch(
+        &self,
+        resource_id: Uuid,
+        drafts: &[TaskDraft],
+        actor_id: Uuid,
+        now: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+                UPDATE task_draft SET status = $1, updated_at = $2, updated_by = $3
+                WHERE resource_id = $4 AND status = $5
+            "#,
+            DraftStatus::Rejected.as_str(),
+            now,
+            actor_id,
+            resource_id,
+            DraftStatus::Pending.as_str(),
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for draft in drafts {
+            sqlx::query!(
+                r#"INSERT INTO task_draft(id, account_id, resource_id, batch_id, position, title, description, type, subject, due_date, status, task_id, created_at, created_by, updated_at, updated_by)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NULL, $12, $13, $14, $15)"#,
+                draft.id,
+                draft.account_id,
+                draft.resource_id,
+                draft.batch_id,
+                draft.position,
+                draft.title,
+                draft.description,
+                draft.task_type.as_str(),
+                draft.subject.as_str(),
+                draft.due_date,
+                draft.status.as_str(),
+                draft.created_at,
+                draft.created_by,
+                draft.updated_at,
+                draft.updated_by,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 草稿列表，按识别时间和批次内顺序排列；来源资源已删除的草稿不返回
+    pub async fn find(
+        &self,
+        account_ids: &[Uuid],
+        resource_id: Option<Uuid>,
+        status: DraftStatus,
+    ) -> Result<Vec<TaskDraft>, AppError> {
+        let drafts = sqlx::query_as!(
+            TaskDraft,
+            r#"
+                SELECT id, account_id, resource_id, batch_id, position, title, description,
+                    type AS "task_type: TaskType",
+                    subject AS "subject: Subject",
+                    due_date,
+                    status AS "status: DraftStatus",
+                    task_id, created_at, created_by, updated_at, updated_by
+                FROM task_draft
+                WHERE account_id = ANY($1)
+                  AND ($2::uuid IS NULL OR resource_id = $2)
+                  AND status = $3
+                  AND EXISTS(SELECT 1 FROM resource r WHERE r.id = task_draft.resource_id AND r.is_deleted = false)
+                ORDER BY created_at DESC, batch_id, position
+            "#,
+            account_ids,
+            resource_id,
+            status.as_str(),
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(drafts)
+    }
+
+    pub async fn find_by_id_and_account(
+        &self,
+        draft_id: Uuid,
+        account_ids: &[Uuid],
+    ) -> Result<TaskDraft, AppError> {
+        let draft = sqlx::query_as!(
+            TaskDraft,
+            r#"
+                SELECT id, account_id, resource_id, batch_id, position, title, description,
+                    type AS "task_type: TaskType",
+                    subject AS "subject: Subject",
+                    due_date,
+                    status AS "status: DraftStatus",
+                    task_id, created_at, created_by, updated_at, updated_by
+                FROM task_draft
+                WHERE id = $1 AND account_id = ANY($2)
+                  AND EXISTS(SELECT 1 FROM resource r WHERE r.id = task_draft.resource_id AND r.is_deleted = false)
+            "#,
+            draft_id,
+            account_ids
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(draft)
+    }
+
+    /// 编辑待确认的草稿，已处理的草稿匹配不到，返回 404
+    pub async fn patch(
+        &self,
+        draft_id: Uuid,
+        account_ids: &[Uuid],
+        actor_id: Uuid,
+        input: &PatchTaskDraftRequest,
+    ) -> Result<TaskDraft, AppError> {
+        let now = chrono::Utc::now();
+        let draft = sqlx::query_as!(
+            TaskDraft,
+            r#"
+            UPDATE task_draft SET
+              title = CASE WHEN $1 THEN $2 ELSE title END,
+              description = CASE WHEN $3 THEN $4 ELSE description END,
+              type = CASE WHEN $5 THEN $6 ELSE type END,
+              subject = CASE WHEN $7 THEN $8 ELSE subject END,
+              due_date = CASE WHEN $9 THEN $10 ELSE due_date END,
+              updated_at = $11,
+              updated_by = $12
+            WHERE id = $13 AND account_id = ANY($14) AND status = $15
+            RETURNING id, account_id, resource_id, batch_id, position, title, description,
+                type AS "task_type: TaskType",
+                subject AS "subject: Subject",
+                due_date,
+                status AS "status: DraftStatus",
+                task_id, created_at, created_by, updated_at, updated_by
+        "#,
+            !input.title.is_missing(),
+            input.title.value().map(String::as_str),
+            !input.description.is_missing(),
+            input.description.value().map(String::as_str),
+            !input.task_type.is_missing(),
+            input.task_type.value().map(|t| t.as_str()),
+            !input.subject.is_missing(),
+            input.subject.value().map(|s| s.as_str()),
+            !input.due_date.is_missing(),
+            input.due_date.value().copied(),
+            now,
+            actor_id,
+            draft_id,
+            account_ids,
+            DraftStatus::Pending.as_str(),
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(draft)
+    }
+
+    /// 在调用方的事务内把待确认的草稿标记为 to 状态并返回；并发处理同一草稿时只有一方能拿到，
+    /// 来源资源已删除的草稿不会被标记
+    pub async fn claim(
+        &self,
+        conn: &mut PgConnection,
+        draft_ids: &[Uuid],
+        account_ids: &[Uuid],
+        to: DraftStatus,
+        actor_id: Uuid,
+    ) -> Result<Vec<TaskDraft>, AppError> {
+        let now = chrono::Utc::now();
+        let drafts = sqlx::query_as!(
+            TaskDraft,
+            r#"
+                UPDATE task_draft SET status = $1, updated_at = $2, updated_by = $3
+                WHERE id = ANY($4) AND account_id = ANY($5) AND status = $6
+                  AND EXISTS(SELECT 1 FROM resource r WHERE r.id = task_draft.resource_id AND r.is_deleted = false)
+                RETURNING id, account_id, resource_id, batch_id, position, title, description,
+                    type AS "task_type: TaskType",
+                    subject AS "subject: Subject",
+                    due_date,
+                    status AS "status: DraftStatus",
+                    task_id, created_at, created_by, updated_at, updated_by
+            "#,
+            to.as_str(),
+            now,
+            actor_id,
+            draft_ids,
+            account_ids,
+            DraftStatus::Pending.as_str(),
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(drafts)
+    }
+
+    pub async fn set_task(
+        &self,
+        conn: &mut PgConnection,
+        draft_id: Uuid,
+        task_id: Uuid,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE task_draft SET task_id = $2 WHERE id = $1",
+            draft_id,
+            task_id
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 该资源最早确认生成、且仍存在的任务，作为 ai_split 关联的父任务
+    pub async fn find_split_root(
+        &self,
+        conn: &mut PgConnection,
+        resource_id: Uuid,
+        exclude_draft_ids: &[Uuid],
+    ) -> Result<Option<Uuid>, AppError> {
+        let task_id = sqlx::query_scalar!(
+            r#"
+                SELECT t.id FROM task_draft d
+                JOIN task t ON t.id = d.task_id AND t.is_deleted = false
+                WHERE d.resource_id = $1 AND d.status = $2 AND NOT d.id = ANY($3)
+                ORDER BY d.created_at, d.position
+                LIMIT 1
+            "#,
+            resource_id,
+            DraftStatus::Accepted.as_str(),
+            exclude_draft_ids,
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(task_id)
+    }
+}
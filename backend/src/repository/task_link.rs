@@ -0,0 +1,317 @@
+use crate::app::from_pool::FromPool;
+use crate::error::AppError;
+use crate::model::task::PlanProgress;
+use crate::model::task_link::{LinkType, LinkedTask, TaskTreeEdge};
+use crate::repository::task::task_columns;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+// 展开任务树的最大深度，防止异常数据导致查询失控
+const MAX_TREE_DEPTH: i32 = 10;
+
+#[derive(Clone)]
+pub struct TaskLinkRepository {
+    pool: PgPool,
+}
+
+impl FromPool for TaskLinkRepository {
+    fn from_pool(pool: PgPool) -> Self {
+        Self::new(pool)
+    }
+}
+
+impl TaskLinkRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 创建关联，已存在时返回 409，会形成环时返回 400
+    ///
+    /// 环检测与插入在同一事务内，并按任务所属账户加事务级 advisory lock，
+    /// 避免并发插入两条互为反向的边
+    pub async fn insert(
+        &self,
+        owner_id: Uuid,
+        parent_task_id: Uuid,
+        child_task_id: Uuid,
+        link_type: LinkType,
+        actor_id: Uuid,
+        created_at: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+        Self::insert_in(
+            &mut tx,
+            owner_id,
+            parent_task_id,
+            child_task_id,
+            link_type,
+            actor_id,
+            created_at,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 在调用方的事务内创建关联，advisory lock 持有到该事务结束
+    pub async fn insert_in(
+        conn: &mut PgConnection,
+        owner_id: Uuid,
+        parent_task_id: Uuid,
+        child_task_id: Uuid,
+        link_type: LinkType,
+        actor_id: Uuid,
+        created_at: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), AppError> {
+        // pg_advisory_xact_lock 返回 void，query! 无法推断列类型，这里用运行时查询
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('task_link:' || $1::text))")
+            .bind(owner_id)
+            .execute(&mut *conn)
+            .await?;
+
+        // child 沿同一张图能走回 parent，说明新边会形成环
+        let creates_cycle = sqlx::query_scalar!(
+            r#"
+                WITH RECURSIVE reachable(id) AS (
+                    SELECT child_task_id FROM task_link
+                    WHERE parent_task_id = $1 AND link_type = ANY($3)
+                    UNION
+                    SELECT l.child_task_id FROM task_link l
+                    JOIN reachable r ON l.parent_task_id = r.id
+                    WHERE l.link_type = ANY($3)
+                )
+                SELECT EXISTS(SELECT 1 FROM reachable WHERE id = $2) AS "exists!"
+            "#,
+            child_task_id,
+            parent_task_id,
+            link_type.graph() as &[&str],
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+        if creates_cycle {
+            return Err(AppError::BadRequest("关联会形成循环".into()));
+        }
+
+        let result = sqlx::query!(
+            r#"INSERT INTO task_link(parent_task_id, child_task_id, link_type, created_at, created_by)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT DO NOTHING"#,
+            parent_task_id,
+            child_task_id,
+            link_type.as_str(),
+            created_at,
+            actor_id,
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict("任务关联已存在".into()));
+        }
+        Ok(())
+    }
+
+    /// 删除关联，未指定类型时删除两任务间的全部关联
+    pub async fn delete(
+        &self,
+        parent_task_id: Uuid,
+        child_task_id: Uuid,
+        link_type: Option<LinkType>,
+    ) -> Result<(), AppError> {
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM task_link
+                WHERE parent_task_id = $1 AND child_task_id = $2
+                  AND ($3::text IS NULL OR link_type = $3)
+            "#,
+            parent_task_id,
+            child_task_id,
+            link_type.map(|t| t.as_str()),
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// 子任务列表（当前任务为 parent）
+    pub async fn find_children(
+        &self,
+        task_id: Uuid,
+        account_ids: &[Uuid],
+        link_type: Option<LinkType>,
+    ) -> Result<Vec<LinkedTask>, AppError> {
+        self.find_linked(
+            task_id,
+            account_ids,
+            link_type,
+            "parent_task_id",
+            "child_task_id",
+        )
+        .await
+    }
+
+    /// 父任务列表（当前任务为 child）
+    pub async fn find_parents(
+        &self,
+        task_id: Uuid,
+        account_ids: &[Uuid],
+        link_type: Option<LinkType>,
+    ) -> Result<Vec<LinkedTask>, AppError> {
+        self.find_linked(
+            task_id,
+            account_ids,
+            link_type,
+            "child_task_id",
+            "parent_task_id",
+        )
+        .await
+    }
+
+    async fn find_linked(
+        &self,
+        task_id: Uuid,
+        account_ids: &[Uuid],
+        link_type: Option<LinkType>,
+        self_column: &str,
+        other_column: &str,
+    ) -> Result<Vec<LinkedTask>, AppError> {
+        let sql = format!(
+            r#"
+                SELECT l.link_type, l.created_at AS linked_at, {}
+                FROM task_link l
+                JOIN task t ON t.id = l.{other_column}
+                WHERE l.{self_column} = $1
+                  AND t.account_id = ANY($2) AND t.is_deleted = false
+                  AND ($3::text IS NULL OR l.link_type = $3)
+                ORDER BY l.created_at, t.id
+            "#,
+            task_columns(Some("t"))
+        );
+        let linked = sqlx::query_as::<_, LinkedTask>(&sql)
+            .bind(task_id)
+            .bind(account_ids)
+            .bind(link_type.map(|t| t.as_str()))
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(linked)
+    }
+
+    /// 以 root 为根的层级关联（includes / ai_split），按深度返回所有边
+    pub async fn find_tree(
+        &self,
+        root_id: Uuid,
+        account_ids: &[Uuid],
+    ) -> Result<Vec<TaskTreeEdge>, AppError> {
+        let sql = format!(
+            r#"
+                WITH RECURSIVE tree AS (
+                    SELECT l.parent_task_id, l.child_task_id, l.link_type, 1 AS depth,
+                           ARRAY[l.parent_task_id, l.child_task_id] AS path
+                    FROM task_link l
+                    WHERE l.parent_task_id = $1 AND l.link_type IN ('includes', 'ai_split')
+                    UNION ALL
+                    SELECT l.parent_task_id, l.child_task_id, l.link_type, tree.depth + 1,
+                           tree.path || l.child_task_id
+                    FROM task_link l
+                    JOIN tree ON l.parent_task_id = tree.child_task_id
+                    WHERE l.link_type IN ('includes', 'ai_split')
+                      AND tree.depth < $3
+                      AND NOT l.child_task_id = ANY(tree.path)
+                )
+                SELECT tree.parent_task_id, tree.link_type, {}
+                FROM tree
+                JOIN task t ON t.id = tree.child_task_id
+                WHERE t.account_id = ANY($2) AND t.is_deleted = false
+                ORDER BY tree.depth, t.created_at, t.id
+            "#,
+            task_columns(Some("t"))
+        );
+        let edges = sqlx::query_as::<_, TaskTreeEdge>(&sql)
+            .bind(root_id)
+            .bind(account_ids)
+            .bind(MAX_TREE_DEPTH)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(edges)
+    }
+
+    /// 给定任务中存在未完成前置任务（depends_on 的 child 仍为 active）的任务 ID
+    pub async fn find_blocked(&self, task_ids: &[Uuid]) -> Result<Vec<Uuid>, AppError> {
+        if task_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids = sqlx::query_scalar!(
+            r#"
+                SELECT DISTINCT l.parent_task_id FROM task_link l
+                JOIN task t ON t.id = l.child_task_id
+                WHERE l.parent_task_id = ANY($1) AND l.link_type = 'depends_on'
+                  AND t.status = 'active' AND t.is_deleted = false
+            "#,
+            task_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// 计划进度：沿 includes 递归展开嵌套计划，统计其中的非计划任务
+    pub async fn find_plan_progress(
+        &self,
+        plan_ids: &[Uuid],
+        now: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(Uuid, PlanProgress)>, AppError> {
+        if plan_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query!(
+            r#"
+                WITH RECURSIVE tree(plan_id, task_id, path) AS (
+                    SELECT l.parent_task_id, l.child_task_id, ARRAY[l.parent_task_id, l.child_task_id]
+                    FROM task_link l
+                    WHERE l.parent_task_id = ANY($1) AND l.link_type = 'includes'
+                    UNION ALL
+                    SELECT tree.plan_id, l.child_task_id, tree.path || l.child_task_id
+                    FROM task_link l
+                    JOIN tree ON l.parent_task_id = tree.task_id
+                    JOIN task p ON p.id = tree.task_id AND p.is_deleted = false
+                    WHERE l.link_type = 'includes' AND NOT l.child_task_id = ANY(tree.path)
+                )
+                SELECT tree.plan_id AS "plan_id!",
+                    COUNT(DISTINCT t.id) FILTER (WHERE t.status <> 'archived') AS "total!",
+                    COUNT(DISTINCT t.id) FILTER (WHERE t.status = 'done') AS "done!",
+                    COUNT(DISTINCT t.id) FILTER (WHERE t.status = 'active') AS "active!",
+                    COUNT(DISTINCT t.id) FILTER (WHERE t.status = 'active' AND t.due_date < $2) AS "overdue!"
+                FROM tree
+                JOIN task t ON t.id = tree.task_id
+                WHERE t.is_deleted = false AND t.type <> 'plan'
+                GROUP BY tree.plan_id
+            "#,
+            plan_ids,
+            now
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                let progress = PlanProgress {
+                    total: r.total,
+                    done: r.done,
+                    active: r.active,
+                    overdue: r.overdue,
+                };
+                (r.plan_id, progress)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,131 @@
+use crate::app::from_pool::FromPool;
+use crate::error::AppError;
+use crate::model::task_resource::{LinkedResource, ResourceTask};
+use crate::repository::resource::resource_columns;
+use crate::repository::task::task_columns;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct TaskResourceRepository {
+    pool: PgPool,
+}
+
+impl FromPool for TaskResourceRepository {
+    fn from_pool(pool: PgPool) -> Self {
+        Self::new(pool)
+    }
+}
+
+impl TaskResourceRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// 关联资源，已关联时返回 409
+    pub async fn insert(
+        &self,
+        task_id: Uuid,
+        resource_id: Uuid,
+        actor_id: Uuid,
+        created_at: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.pool.acquire().await?;
+        self.insert_in(&mut conn, task_id, resource_id, actor_id, created_at)
+            .await
+    }
+
+    /// 在调用方的事务内关联资源
+    pub async fn insert_in(
+        &self,
+        conn: &mut PgConnection,
+        task_id: Uuid,
+        resource_id: Uuid,
+        actor_id: Uuid,
+        created_at: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), AppError> {
+        let result = sqlx::query!(
+            r#"INSERT INTO task_resource(task_id, resource_id, created_at, created_by)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT DO NOTHING"#,
+            task_id,
+            resource_id,
+            created_at,
+            actor_id,
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict("资源已关联到该任务".into()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete(&self, task_id: Uuid, resource_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query!(
+            r#"DELETE FROM task_resource WHERE task_id = $1 AND resource_id = $2"#,
+            task_id,
+            resource_id,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// 任务关联的资源（不含已删除资源）
+    pub async fn find_resources(
+        &self,
+        task_id: Uuid,
+        account_ids: &[Uuid],
+    ) -> Result<Vec<LinkedResource>, AppError> {
+        let sql = format!(
+            r#"
+                SELECT tr.created_at AS linked_at, {}
+                FROM task_resource tr
+                JOIN resource r ON r.id = tr.resource_id
+                WHERE tr.task_id = $1
+                  AND r.account_id = ANY($2) AND r.is_deleted = false
+                ORDER BY tr.created_at, r.id
+            "#,
+            resource_columns(Some("r"))
+        );
+        let resources = sqlx::query_as::<_, LinkedResource>(&sql)
+            .bind(task_id)
+            .bind(account_ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(resources)
+    }
+
+    /// 使用该资源的任务（不含已删除任务）
+    pub async fn find_tasks(
+        &self,
+        resource_id: Uuid,
+        account_ids: &[Uuid],
+    ) -> Result<Vec<ResourceTask>, AppError> {
+        let sql = format!(
+            r#"
+                SELECT tr.created_at AS linked_at, {}
+                FROM task_resource tr
+                JOIN task t ON t.id = tr.task_id
+                WHERE tr.resource_id = $1
+                  AND t.account_id = ANY($2) AND t.is_deleted = false
+                ORDER BY tr.created_at, t.id
+            "#,
+            task_columns(Some("t"))
+        );
+        let tasks = sqlx::query_as::<_, ResourceTask>(&sql)
+            .bind(resource_id)
+            .bind(account_ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(tasks)
+    }
+}
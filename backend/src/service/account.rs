@@ -1,6 +1,10 @@
 use crate::app::context::Repos;
 use crate::error::AppError;
 use crate::model::account::{Account, AccountCreate};
+use crate::model::family::FamilyCreate;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::SaltString;
+use argon2::{Argon2, PasswordHasher};
 
 #[derive(Clone)]
 pub struct AccountService {
@@ -15,7 +19,30 @@ impl AccountService {
         self.repos.account.get_by_username(&name).await
     }
 
-    pub async fn create(&self, account: AccountCreate) -> Result<(), AppError> {
-        self.repos.account.insert(account).await
+    /// 注册家长账户，同时创建其家庭
+    pub async fn register_parent(&self, account: AccountCreate) -> Result<(), AppError> {
+        if let Some(username) = &account.username {
+            if self.repos.account.exists_username(username).await? {
+                return Err(AppError::BadRequest("用户名已存在".into()));
+            }
+        }
+
+        let family = FamilyCreate {
+            id: account.family_id,
+            name: account.nickname.clone(),
+            created_at: account.created_at,
+            created_by: account.id,
+        };
+        self.repos.account.insert_with_family(family, account).await
     }
 }
+
+/// Argon2 密码哈希
+pub fn hash_password(password: &str) -> Result<String, AppError> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hashed = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|_| AppError::Internal)?
+        .to_string();
+    Ok(hashed)
+}
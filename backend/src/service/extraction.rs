@@ -0,0 +1,361 @@
+use crate::app::context::Repos;
+use crate::error::AppError;
+use crate::llm::{LlmImage, LlmPrompt, LlmProvider};
+use crate::model::resource::{FileType, Resource};
+use crate::model::task::{CreateTaskRequest, Subject, TaskType};
+use crate::model::task_draft::{DraftStatus, TaskDraft};
+use crate::storage::Storage;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// 单次识别最多保留的任务数，避免模型输出失控
+const MAX_DRAFTS: usize = 20;
+const MAX_TITLE_CHARS: usize = 100;
+// 图片 OCR 结果少于该字数时不采用
+const MIN_OCR_CHARS: usize = 10;
+// 截止日期按北京时间理解
+const LOCAL_OFFSET_SECS: i32 = 8 * 3600;
+
+// 模型输出结构，对应 PRD 8.2；字段都按可缺省处理，由 validate 兜底
+#[derive(Debug, Deserialize)]
+struct LlmOutput {
+    #[serde(default)]
+    tasks: Vec<LlmTask>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LlmTask {
+    title: Option<String>,
+    #[serde(rename = "type")]
+    task_type: Option<String>,
+    subject: Option<String>,
+    description: Option<String>,
+    due_date: Option<String>,
+    items: Option<Vec<String>>, // 模型可能输出 null
+}
+
+/// 截图导入：调用 LLM 把资源识别为任务草稿，确认流程见 TaskDraftService
+#[derive(Clone)]
+pub struct ExtractionService {
+    repos: Repos,
+    storage: Arc<dyn Storage>,
+    llm: Option<Arc<dyn LlmProvider>>,
+}
+
+impl ExtractionService {
+    pub fn new(repos: Repos, storage: Arc<dyn Storage>, llm: Option<Arc<dyn LlmProvider>>) -> Self {
+        Self {
+            repos,
+            storage,
+            llm,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.llm.is_some()
+    }
+
+    /// 识别资源中的任务，校验后存为一批待确认的草稿，归属资源所在账户（后台任务执行）
+    pub async fn extract(
+        &self,
+        resource_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Vec<TaskDraft>, AppError> {
+        let llm = self
+            .llm
+            .as_ref()
+            .ok_or_else(|| AppError::BadRequest("未启用 AI 服务".into()))?;
+
+        let account_ids = self.repos.account.visible_account_ids(account_id).await?;
+        let resource = self
+            .repos
+            .resource
+            .find_by_id_and_account(resource_id, &account_ids)
+            .await?;
+
+        let now = Utc::now();
+        let prompt = self.build_prompt(&resource, now).await?;
+        let raw = llm.complete(&prompt).await?;
+        let requests = parse_drafts(&raw, resource.account_id)?;
+
+        let batch_id = Uuid::new_v4();
+        let drafts: Vec<TaskDraft> = requests
+            .into_iter()
+            .enumerate()
+            .map(|(position, request)| TaskDraft {
+                id: Uuid::new_v4(),
+                account_id: resource.account_id,
+                resource_id: resource.id,
+                batch_id,
+                position: position as i32,
+                title: request.title,
+                description: request.description,
+                task_type: request.task_type,
+                subject: request.subject,
+                due_date: request.due_date,
+                status: DraftStatus::Pending,
+                task_id: None,
+                created_at: now,
+                created_by: account_id,
+                updated_at: now,
+                updated_by: account_id,
+            })
+            .collect();
+        self.repos
+            .task_draft
+            .insert_batch(resource.id, &drafts, account_id, &now)
+            .await?;
+
+        Ok(drafts)
+    }
+
+    async fn build_prompt(
+        &self,
+        resource: &Resource,
+        now: DateTime<Utc>,
+    ) -> Result<LlmPrompt, AppError> {
+        let today = now.with_timezone(&local_offset()).date_naive();
+        let mut text = format!("今天是 {}。资料文件名：{}。", today, resource.file_name);
+        let mut images = Vec::new();
+
+        // 已提取到文字时只发文字（比发图片便宜），OCR 结果过短视为识别失败，图片仍直接交给模型
+        let extracted = resource.extracted_text.as_deref().filter(|t| {
+            resource.file_type != FileType::Image
+                || t.chars().filter(|c| !c.is_whitespace()).count() >= MIN_OCR_CHARS
+        });
+        if let Some(extracted) = extracted.filter(|t| !t.is_empty()) {
+            text.push_str("请识别以下文字中布置的作业和学习任务：\n");
+            text.push_str(extracted);
+        } else if resource.file_type == FileType::Image {
+            text.push_str("请识别图片中布置的作业和学习任务。");
+            images.push(LlmImage {
+                mime: resource.content_type(),
+                data: self.storage.get(&resource.storage_key()).await?,
+            });
+        } else {
+            return Err(AppError::BadRequest(
+                "该资料暂不支持识别，请上传图片或等待文字提取完成".into(),
+            ));
+        }
+
+        Ok(LlmPrompt {
+            system: system_prompt(),
+            text,
+            images,
+        })
+    }
+}
+
+fn system_prompt() -> String {
+    let types = TaskType::ALL.map(|t| t.as_str()).join(" / ");
+    let subjects = Subject::ALL.map(|s| s.as_str()).join(" / ");
+    format!(
+        r#"你是学习任务整理助手。从老师布置作业的截图或文字中拆分出独立的任务，只输出 JSON，不要输出其它内容：
+{{"tasks": [{{"title": "任务标题", "type": "任务类型", "subject": "学科", "due_date": "YYYY-MM-DD 或 null", "items": ["具体题目或要求"]}}]}}
+type 取值：{types}；subject 取值：{subjects}，无法判断时用 other。
+截止日期只在原文明确给出时填写，"明天""周五"等相对日期按今天换算。没有识别到任务时输出 {{"tasks": []}}。"#
+    )
+}
+
+/// 解析模型输出并逐条校验：缺标题、类型或学科不在取值范围内的丢弃，未给出学科时视为 other
+fn parse_drafts(raw: &str, owner_id: Uuid) -> Result<Vec<CreateTaskRequest>, AppError> {
+    let output: LlmOutput = serde_json::from_str(strip_json(raw)).map_err(|e| {
+        tracing::warn!(err = ?e, raw, "LLM 输出不是合法的任务 JSON");
+        AppError::Upstream("AI 返回的内容无法解析".into())
+    })?;
+
+    Ok(output
+        .tasks
+        .into_iter()
+        .filter_map(|task| validate(task, owner_id))
+        .take(MAX_DRAFTS)
+        .collect())
+}
+
+fn validate(task: LlmTask, owner_id: Uuid) -> Option<CreateTaskRequest> {
+    let title: String = task
+        .title
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .take(MAX_TITLE_CHARS)
+        .collect();
+    if title.is_empty() {
+        return None;
+    }
+
+    // 取值不在范围内说明模型没有按要求输出，不猜测，整条丢弃
+    let Some(task_type) = task
+        .task_type
+        .as_deref()
+        .and_then(|raw| TaskType::ALL.into_iter().find(|t| t.as_str() == raw))
+    else {
+        tracing::warn!(task_type = ?task.task_type, %title, "LLM 输出的任务类型不合法，已丢弃");
+        return None;
+    };
+    let subject = match task.subject.as_deref() {
+        None => Subject::Other,
+        Some(raw) => match Subject::ALL.into_iter().find(|s| s.as_str() == raw) {
+            Some(subject) => subject,
+            None => {
+                tracing::warn!(subject = raw, %title, "LLM 输出的学科不合法，已丢弃");
+                return None;
+            }
+        },
+    };
+
+    // items 合并进描述，每行一项
+    let mut lines: Vec<String> = task
+        .description
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+        .into_iter()
+        .collect();
+    lines.extend(
+        task.items
+            .unwrap_or_default()
+            .iter()
+            .map(|item| item.trim())
+            .filter(|item| !item.is_empty())
+            .map(|item| format!("- {}", item)),
+    );
+    let description = (!lines.is_empty()).then(|| lines.join("\n"));
+
+    Some(CreateTaskRequest {
+        account_id: Some(owner_id),
+        title,
+        description,
+        task_type,
+        subject,
+        due_date: task.due_date.as_deref().and_then(parse_due_date),
+        auto_complete: false,
+    })
+}
+
+// 支持 RFC 3339 和 YYYY-MM-DD（视为当天 23:59:59 截止）
+fn parse_due_date(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(raw) {
+        return Some(at.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(raw, "%Y-%m-%d").ok()?;
+    date.and_time(NaiveTime::from_hms_opt(23, 59, 59)?)
+        .and_local_timezone(local_offset())
+        .single()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+// 模型常把 JSON 包在 ```json 代码块或说明文字里，取最外层的花括号
+fn strip_json(raw: &str) -> &str {
+    match (raw.find('{'), raw.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &raw[start..=end],
+        _ => raw,
+    }
+}
+
+fn local_offset() -> FixedOffset {
+    FixedOffset::east_opt(LOCAL_OFFSET_SECS).expect("合法的时区偏移")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::mock::MockProvider;
+    use crate::test_support::{create_parent, create_resource, local_storage};
+    use sqlx::PgPool;
+
+    async fn service(pool: PgPool, provider: MockProvider) -> (ExtractionService, Repos) {
+        let repos = Repos::new(pool);
+        let service = ExtractionService::new(
+            repos.clone(),
+            local_storage().await,
+            Some(Arc::new(provider)),
+        );
+        (service, repos)
+    }
+
+    // 已提取文字的 PDF，识别时只发文字，不读取存储
+    async fn pdf_with_text(repos: &Repos, account_id: Uuid) -> Resource {
+        let resource = create_resource(repos, account_id, FileType::Pdf, "hash-pdf").await;
+        repos
+            .resource
+            .set_extracted_text(
+                resource.id,
+                "数学：练习册第1-3题，明天交。英语：预习第三单元。",
+            )
+            .await
+            .unwrap();
+        resource
+    }
+
+    #[sqlx::test]
+    async fn mock_output_becomes_pending_drafts(pool: PgPool) {
+        let (service, repos) = service(pool, MockProvider::default()).await;
+        let account_id = create_parent(&repos).await;
+        let resource = pdf_with_text(&repos, account_id).await;
+
+        let drafts = service.extract(resource.id, account_id).await.unwrap();
+
+        assert_eq!(drafts.len(), 2);
+        assert_eq!(drafts[0].title, "数学作业");
+        assert_eq!(drafts[0].task_type, TaskType::Homework);
+        assert_eq!(drafts[0].subject, Subject::Math);
+        assert_eq!(
+            drafts[0].description.as_deref(),
+            Some("- 第1题\n- 第2题\n- 第3题")
+        );
+        assert_eq!(drafts[1].title, "预习英语第三单元");
+        assert_eq!(drafts[1].task_type, TaskType::Preview);
+        assert_eq!(drafts[1].subject, Subject::English);
+        assert_eq!(drafts[1].description, None);
+        assert!(drafts.iter().all(|d| d.batch_id == drafts[0].batch_id));
+
+        let stored = repos
+            .task_draft
+            .find(&[account_id], Some(resource.id), DraftStatus::Pending)
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 2);
+    }
+
+    #[sqlx::test]
+    async fn unknown_type_or_subject_is_rejected(pool: PgPool) {
+        let output = r#"```json
+{"tasks": [
+  {"title": "作文", "type": "essay", "subject": "chinese"},
+  {"title": "历史背诵", "type": "homework", "subject": "history"},
+  {"title": "", "type": "homework", "subject": "math"},
+  {"title": "口算练习", "type": "practice", "subject": "math", "due_date": "2025-01-10"},
+  {"title": "整理错题", "type": "review"}
+]}
+```"#;
+        let (service, repos) = service(pool, MockProvider::new(output)).await;
+        let account_id = create_parent(&repos).await;
+        let resource = pdf_with_text(&repos, account_id).await;
+
+        let drafts = service.extract(resource.id, account_id).await.unwrap();
+
+        let titles: Vec<&str> = drafts.iter().map(|d| d.title.as_str()).collect();
+        assert_eq!(titles, ["口算练习", "整理错题"]);
+        assert_eq!(drafts[0].subject, Subject::Math);
+        // 日期按北京时间当天 23:59:59 截止
+        assert_eq!(
+            drafts[0].due_date.map(|d| d.to_rfc3339()).as_deref(),
+            Some("2025-01-10T15:59:59+00:00")
+        );
+        assert_eq!(drafts[1].subject, Subject::Other);
+    }
+
+    #[sqlx::test]
+    async fn unparsable_output_is_upstream_error(pool: PgPool) {
+        let (service, repos) = service(pool, MockProvider::new("抱歉，我无法识别这张图片")).await;
+        let account_id = create_parent(&repos).await;
+        let resource = pdf_with_text(&repos, account_id).await;
+
+        let err = service.extract(resource.id, account_id).await.unwrap_err();
+
+        assert!(matches!(err, AppError::Upstream(_)));
+    }
+}
@@ -0,0 +1,259 @@
+use crate::app::context::Repos;
+use crate::error::AppError;
+use crate::model::account::{Account, AccountCreate, AccountResponse, Role};
+use crate::model::family::{CreateChildRequest, FamilyResponse, UpdateChildRequest};
+use crate::service::account::hash_password;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct FamilyService {
+    repos: Repos,
+}
+
+impl FamilyService {
+    pub fn new(repos: Repos) -> Self {
+        Self { repos }
+    }
+
+    pub async fn get(&self, account_id: Uuid) -> Result<FamilyResponse, AppError> {
+        let me = self.repos.account.get_by_id(account_id).await?;
+        let family = self.repos.family.find_by_id(me.family_id).await?;
+        let members = self.repos.account.find_by_family(family.id).await?;
+
+        Ok(FamilyResponse {
+            id: family.id,
+            name: family.name,
+            members: members
+                .into_iter()
+                .map(AccountResponse::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub async fn list_children(&self, parent_id: Uuid) -> Result<Vec<Account>, AppError> {
+        let parent = self.require_parent(parent_id).await?;
+        let members = self.repos.account.find_by_family(parent.family_id).await?;
+        let mut children = Vec::new();
+        for member in members {
+            if !member.is_parent()? {
+                children.push(member);
+            }
+        }
+        Ok(children)
+    }
+
+    pub async fn create_child(
+        &self,
+        parent_id: Uuid,
+        input: CreateChildRequest,
+    ) -> Result<Account, AppError> {
+        let parent = self.require_parent(parent_id).await?;
+        if self.repos.account.exists_username(&input.username).await? {
+            return Err(AppError::BadRequest("用户名已存在".into()));
+        }
+
+        let uid = Uuid::new_v4();
+        let now = chrono::Utc::now();
+        let account_create = AccountCreate {
+            id: uid,
+            username: Some(input.username),
+            nickname: input.nickname,
+            password_hash: Some(hash_password(&input.password)?),
+            role: Role::Child.as_str().to_string(),
+            created_at: now,
+            updated_at: now,
+            created_by: parent.id,
+            updated_by: parent.id,
+            is_deleted: false,
+            family_id: parent.family_id,
+        };
+        self.repos.account.insert(account_create).await?;
+
+        self.repos.account.get_by_id(uid).await
+    }
+
+    pub async fn update_child(
+        &self,
+        parent_id: Uuid,
+        child_id: Uuid,
+        input: UpdateChildRequest,
+    ) -> Result<(), AppError> {
+        let child = self.require_child_of(parent_id, child_id).await?;
+        let password_hash = match &input.password {
+            Some(password) => Some(hash_password(password)?),
+            None => None,
+        };
+
+        self.repos
+            .account
+            .update_profile(child.id, input.nickname, password_hash, parent_id)
+            .await?;
+
+        // 重置密码后让孩子重新登录
+        if input.password.is_some() {
+            self.repos
+                .session
+                .revoke_account_sessions(child.id, parent_id)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn delete_child(&self, parent_id: Uuid, child_id: Uuid) -> Result<(), AppError> {
+        let child = self.require_child_of(parent_id, child_id).await?;
+        self.repos.account.delete(child.id, parent_id).await?;
+        self.repos
+            .session
+            .revoke_account_sessions(child.id, parent_id)
+            .await
+    }
+
+    async fn require_parent(&self, account_id: Uuid) -> Result<Account, AppError> {
+        let account = self.repos.account.get_by_id(account_id).await?;
+        if !account.is_parent()? {
+            return Err(AppError::Forbidden);
+        }
+        Ok(account)
+    }
+
+    /// 校验 child_id 是当前家长家庭中的孩子
+    async fn require_child_of(&self, parent_id: Uuid, child_id: Uuid) -> Result<Account, AppError> {
+        let parent = self.require_parent(parent_id).await?;
+        let child = self.repos.account.get_by_id(child_id).await?;
+        if child.family_id != parent.family_id || child.is_parent()? {
+            return Err(AppError::NotFound);
+        }
+        Ok(child)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::task::{CreateTaskRequest, Subject, TaskListQuery, TaskType};
+    use crate::service::task::TaskService;
+    use crate::test_support::{create_parent, create_task};
+    use sqlx::PgPool;
+
+    fn child(username: &str) -> CreateChildRequest {
+        CreateChildRequest {
+            username: username.to_string(),
+            password: "password123".into(),
+            nickname: username.to_string(),
+        }
+    }
+
+    async fn list_titles(tasks: &TaskService, account_id: Uuid) -> Vec<String> {
+        let query: TaskListQuery = serde_json::from_str("{}").unwrap();
+        let (items, _) = tasks.list(account_id, &query).await.unwrap();
+        let mut titles: Vec<String> = items.into_iter().map(|t| t.title).collect();
+        titles.sort();
+        titles
+    }
+
+    #[sqlx::test]
+    async fn tasks_are_visible_within_family_only(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let family = FamilyService::new(repos.clone());
+        let tasks = TaskService::new(repos.clone());
+        let parent_id = create_parent(&repos).await;
+        let alice = family
+            .create_child(parent_id, child("alice"))
+            .await
+            .unwrap();
+        let bob = family.create_child(parent_id, child("bob")).await.unwrap();
+        let other_parent = create_parent(&repos).await;
+        create_task(&repos, parent_id, "家长的任务").await;
+        create_task(&repos, alice.id, "alice 的作业").await;
+        let bob_task = create_task(&repos, bob.id, "bob 的作业").await;
+
+        // 家长看到整个家庭的任务，孩子只看到自己的
+        assert_eq!(
+            list_titles(&tasks, parent_id).await,
+            ["alice 的作业", "bob 的作业", "家长的任务"]
+        );
+        assert_eq!(list_titles(&tasks, alice.id).await, ["alice 的作业"]);
+        assert!(list_titles(&tasks, other_parent).await.is_empty());
+
+        assert_eq!(
+            tasks.get(bob_task.id, parent_id).await.unwrap().id,
+            bob_task.id
+        );
+        assert!(matches!(
+            tasks.get(bob_task.id, alice.id).await,
+            Err(AppError::NotFound)
+        ));
+        assert!(matches!(
+            tasks.get(bob_task.id, other_parent).await,
+            Err(AppError::NotFound)
+        ));
+
+        // 孩子不能按兄弟姐妹筛选，也不能替其创建任务
+        let query: TaskListQuery =
+            serde_json::from_value(serde_json::json!({ "account_id": bob.id })).unwrap();
+        assert!(matches!(
+            tasks.list(alice.id, &query).await,
+            Err(AppError::Forbidden)
+        ));
+        let create = CreateTaskRequest {
+            account_id: Some(bob.id),
+            title: "代写作业".into(),
+            description: None,
+            task_type: TaskType::Homework,
+            subject: Subject::Math,
+            due_date: None,
+            auto_complete: false,
+        };
+        assert!(matches!(
+            tasks.create(alice.id, &create).await,
+            Err(AppError::Forbidden)
+        ));
+        assert_eq!(
+            tasks.create(parent_id, &create).await.unwrap().account_id,
+            bob.id
+        );
+    }
+
+    #[sqlx::test]
+    async fn duplicate_username_is_bad_request(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let family = FamilyService::new(repos.clone());
+        let parent_id = create_parent(&repos).await;
+        let alice = family
+            .create_child(parent_id, child("alice"))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            family.create_child(parent_id, child("alice")).await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        // 并发请求都通过了存在性检查时，由唯一索引兜底，同样返回 400
+        let now = chrono::Utc::now();
+        let racing = AccountCreate {
+            id: Uuid::new_v4(),
+            username: Some("alice".into()),
+            nickname: "alice".into(),
+            password_hash: None,
+            role: Role::Child.as_str().to_string(),
+            created_at: now,
+            updated_at: now,
+            created_by: parent_id,
+            updated_by: parent_id,
+            is_deleted: false,
+            family_id: parent_id,
+        };
+        match repos.account.insert(racing).await {
+            Err(AppError::BadRequest(message)) => assert_eq!(message, "用户名已存在"),
+            other => panic!("unexpected: {:?}", other),
+        }
+
+        // 删除后用户名可以再次使用
+        family.delete_child(parent_id, alice.id).await.unwrap();
+        family
+            .create_child(parent_id, child("alice"))
+            .await
+            .unwrap();
+    }
+}
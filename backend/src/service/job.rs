@@ -0,0 +1,356 @@
+use crate::app::context::Repos;
+use crate::error::AppError;
+use crate::model::job::{Job, JobListQuery, JobPayload, JobStatus};
+use chrono::{Duration, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+
+const DEFAULT_LIST_SIZE: i64 = 20;
+const MAX_LIST_SIZE: i64 = 100;
+// 重试间隔上限（秒）
+const MAX_RETRY_DELAY_SECS: i64 = 3600;
+
+/// 后台任务队列：接口侧入队 / 查询，worker 侧抢占 / 回写结果
+#[derive(Clone)]
+pub struct JobService {
+    repos: Repos,
+}
+
+impl JobService {
+    pub fn new(repos: Repos) -> Self {
+        Self { repos }
+    }
+
+    pub async fn enqueue(&self, payload: &JobPayload, account_id: Uuid) -> Result<Job, AppError> {
+        self.repos.job.enqueue(payload, Some(account_id)).await
+    }
+
+    pub async fn get(&self, job_id: Uuid, account_id: Uuid) -> Result<Job, AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        self.repos
+            .job
+            .find_by_id_and_account(job_id, &account_ids)
+            .await
+    }
+
+    pub async fn list(&self, account_id: Uuid, query: &JobListQuery) -> Result<Vec<Job>, AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_LIST_SIZE)
+            .clamp(1, MAX_LIST_SIZE);
+        self.repos
+            .job
+            .find(&account_ids, query.status, query.kind.as_deref(), limit)
+            .await
+    }
+
+    /// 死信任务重新入队，其它状态返回 409
+    pub async fn retry(&self, job_id: Uuid, account_id: Uuid) -> Result<Job, AppError> {
+        let job = self.get(job_id, account_id).await?;
+        if job.status != JobStatus::Dead {
+            return Err(AppError::Conflict("只有失败的任务可以重试".into()));
+        }
+        let account_ids = self.visible_account_ids(account_id).await?;
+        match self.repos.job.revive(job_id, &account_ids).await {
+            // 并发重试时只有一方成功
+            Err(AppError::NotFound) => Err(AppError::Conflict("任务已重新排队".into())),
+            other => other,
+        }
+    }
+
+    pub async fn claim(&self, worker_id: &str) -> Result<Option<Job>, AppError> {
+        self.repos.job.claim(worker_id).await
+    }
+
+    pub async fn succeed(
+        &self,
+        job_id: Uuid,
+        worker_id: &str,
+        result: Option<Value>,
+    ) -> Result<(), AppError> {
+        self.repos.job.succeed(job_id, worker_id, result).await
+    }
+
+    /// 记录失败：可重试且未用完次数时按指数退避重新排队，否则进入死信
+    pub async fn fail(
+        &self,
+        job: &Job,
+        worker_id: &str,
+        error: &str,
+        retryable: bool,
+        retry_base_secs: u64,
+    ) -> Result<JobStatus, AppError> {
+        let retry_at = (retryable && job.attempts < job.max_attempts).then(|| {
+            Utc::now() + Duration::seconds(retry_delay_secs(job.attempts, retry_base_secs))
+        });
+        self.repos
+            .job
+            .fail(job.id, worker_id, error, retry_at)
+            .await?;
+
+        Ok(match retry_at {
+            Some(_) => JobStatus::Queued,
+            None => JobStatus::Dead,
+        })
+    }
+
+    /// 锁定超过 stale_secs 仍未完成的任务重新排队，次数用完的进入死信；返回 (重新排队数, 进入死信数)
+    pub async fn requeue_stale(&self, stale_secs: u64) -> Result<(u64, u64), AppError> {
+        let locked_before = Utc::now() - Duration::seconds(stale_secs as i64);
+        self.repos
+            .job
+            .requeue_stale(locked_before, "执行超时，worker 未回写结果")
+            .await
+    }
+
+    async fn visible_account_ids(&self, account_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        self.repos.account.visible_account_ids(account_id).await
+    }
+}
+
+// 第 attempts 次执行失败后的重试间隔：base * 2^(attempts-1)，不超过 MAX_RETRY_DELAY_SECS
+fn retry_delay_secs(attempts: i32, base_secs: u64) -> i64 {
+    let exp = (attempts - 1).clamp(0, 16) as u32;
+    base_secs
+        .saturating_mul(1 << exp)
+        .min(MAX_RETRY_DELAY_SECS as u64) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_parent;
+    use sqlx::PgPool;
+
+    fn thumbnail() -> JobPayload {
+        JobPayload::Thumbnail {
+            resource_id: Uuid::new_v4(),
+        }
+    }
+
+    // 把重试时间提前到现在，模拟退避时间已到
+    async fn make_due(pool: &PgPool, job_id: Uuid) {
+        sqlx::query("UPDATE job SET run_at = now() WHERE id = $1")
+            .bind(job_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_cap() {
+        assert_eq!(retry_delay_secs(1, 30), 30);
+        assert_eq!(retry_delay_secs(2, 30), 60);
+        assert_eq!(retry_delay_secs(3, 30), 120);
+        assert_eq!(retry_delay_secs(7, 30), 1920);
+        assert_eq!(retry_delay_secs(8, 30), MAX_RETRY_DELAY_SECS);
+        assert_eq!(retry_delay_secs(100, 30), MAX_RETRY_DELAY_SECS);
+        assert_eq!(retry_delay_secs(2, u64::MAX), MAX_RETRY_DELAY_SECS);
+        // 未执行过按首次计算
+        assert_eq!(retry_delay_secs(0, 30), 30);
+    }
+
+    #[sqlx::test]
+    async fn claim_skips_locked_jobs(pool: PgPool) {
+        let repos = Repos::new(pool.clone());
+        let service = JobService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let first = service.enqueue(&thumbnail(), account_id).await.unwrap();
+        let second = service.enqueue(&thumbnail(), account_id).await.unwrap();
+
+        // 另一个事务锁住第一条，抢占时跳过而不是等待
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("SELECT id FROM job WHERE id = $1 FOR UPDATE")
+            .bind(first.id)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+
+        let claimed = service.claim("w1").await.unwrap().unwrap();
+        assert_eq!(claimed.id, second.id);
+        assert_eq!(claimed.status, JobStatus::Running);
+        assert_eq!(claimed.attempts, 1);
+        assert_eq!(claimed.locked_by.as_deref(), Some("w1"));
+        assert!(claimed.locked_at.is_some());
+        assert!(service.claim("w2").await.unwrap().is_none());
+
+        tx.rollback().await.unwrap();
+        let claimed = service.claim("w2").await.unwrap().unwrap();
+        assert_eq!(claimed.id, first.id);
+        assert!(service.claim("w3").await.unwrap().is_none());
+    }
+
+    #[sqlx::test]
+    async fn failures_back_off_then_go_dead(pool: PgPool) {
+        let repos = Repos::new(pool.clone());
+        let service = JobService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let job = service.enqueue(&thumbnail(), account_id).await.unwrap();
+        assert_eq!(job.max_attempts, 5);
+
+        for attempts in 1..job.max_attempts {
+            let claimed = service.claim("w1").await.unwrap().unwrap();
+            assert_eq!(claimed.attempts, attempts);
+            let before = Utc::now();
+            let status = service
+                .fail(&claimed, "w1", "渲染失败", true, 10)
+                .await
+                .unwrap();
+            assert_eq!(status, JobStatus::Queued);
+
+            let queued = service.get(job.id, account_id).await.unwrap();
+            assert_eq!(queued.status, JobStatus::Queued);
+            assert_eq!(queued.last_error.as_deref(), Some("渲染失败"));
+            assert!(queued.locked_by.is_none());
+            let delay = (queued.run_at - before).num_seconds();
+            let expected = retry_delay_secs(attempts, 10);
+            assert!((expected - 1..=expected).contains(&delay), "{}", delay);
+            // 退避时间未到不会被抢占
+            assert!(service.claim("w1").await.unwrap().is_none());
+            make_due(&pool, job.id).await;
+        }
+
+        // 最后一次失败进入死信
+        let claimed = service.claim("w1").await.unwrap().unwrap();
+        assert_eq!(claimed.attempts, claimed.max_attempts);
+        let status = service
+            .fail(&claimed, "w1", "渲染失败", true, 10)
+            .await
+            .unwrap();
+        assert_eq!(status, JobStatus::Dead);
+        let dead = service.get(job.id, account_id).await.unwrap();
+        assert_eq!(dead.status, JobStatus::Dead);
+        assert!(dead.finished_at.is_some());
+        assert!(service.claim("w1").await.unwrap().is_none());
+    }
+
+    #[sqlx::test]
+    async fn non_retryable_failure_goes_dead(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = JobService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        service.enqueue(&thumbnail(), account_id).await.unwrap();
+
+        let claimed = service.claim("w1").await.unwrap().unwrap();
+        let status = service
+            .fail(&claimed, "w1", "资源不存在", false, 10)
+            .await
+            .unwrap();
+        assert_eq!(status, JobStatus::Dead);
+        assert_eq!(claimed.attempts, 1);
+    }
+
+    #[sqlx::test]
+    async fn only_lock_holder_can_finish(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = JobService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let job = service.enqueue(&thumbnail(), account_id).await.unwrap();
+        let claimed = service.claim("w1").await.unwrap().unwrap();
+
+        assert!(matches!(
+            service.succeed(job.id, "w2", None).await,
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            service.fail(&claimed, "w2", "失败", true, 10).await,
+            Err(AppError::Conflict(_))
+        ));
+        assert_eq!(
+            service.get(job.id, account_id).await.unwrap().status,
+            JobStatus::Running
+        );
+
+        let result = serde_json::json!({ "ok": true });
+        service
+            .succeed(job.id, "w1", Some(result.clone()))
+            .await
+            .unwrap();
+        let done = service.get(job.id, account_id).await.unwrap();
+        assert_eq!(done.status, JobStatus::Succeeded);
+        assert_eq!(done.result, Some(result));
+        assert!(done.finished_at.is_some());
+        // 已完成的任务不能再回写
+        assert!(matches!(
+            service.succeed(job.id, "w1", None).await,
+            Err(AppError::Conflict(_))
+        ));
+    }
+
+    #[sqlx::test]
+    async fn stale_jobs_are_requeued_or_dead(pool: PgPool) {
+        let repos = Repos::new(pool.clone());
+        let service = JobService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let retrying = service.enqueue(&thumbnail(), account_id).await.unwrap();
+        let exhausted = service.enqueue(&thumbnail(), account_id).await.unwrap();
+        service.claim("w1").await.unwrap().unwrap();
+        service.claim("w1").await.unwrap().unwrap();
+        sqlx::query("UPDATE job SET attempts = max_attempts WHERE id = $1")
+            .bind(exhausted.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // 未超时的不动
+        assert_eq!(service.requeue_stale(3600).await.unwrap(), (0, 0));
+        assert_eq!(service.requeue_stale(0).await.unwrap(), (1, 1));
+
+        let requeued = service.get(retrying.id, account_id).await.unwrap();
+        assert_eq!(requeued.status, JobStatus::Queued);
+        assert!(requeued.locked_by.is_none());
+        assert!(requeued.last_error.is_some());
+        let dead = service.get(exhausted.id, account_id).await.unwrap();
+        assert_eq!(dead.status, JobStatus::Dead);
+        assert!(dead.finished_at.is_some());
+
+        // 原 worker 迟到的结果被拒绝，任务由下一个 worker 接管
+        assert!(matches!(
+            service.succeed(retrying.id, "w1", None).await,
+            Err(AppError::Conflict(_))
+        ));
+        let claimed = service.claim("w2").await.unwrap().unwrap();
+        assert_eq!(claimed.id, retrying.id);
+        assert_eq!(claimed.attempts, 2);
+    }
+
+    #[sqlx::test]
+    async fn retry_requeues_dead_job(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = JobService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let other_id = create_parent(&repos).await;
+        let job = service.enqueue(&thumbnail(), account_id).await.unwrap();
+
+        // 未进入死信的任务不能重试
+        assert!(matches!(
+            service.retry(job.id, account_id).await,
+            Err(AppError::Conflict(_))
+        ));
+
+        let claimed = service.claim("w1").await.unwrap().unwrap();
+        service
+            .fail(&claimed, "w1", "资源不存在", false, 10)
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.retry(job.id, other_id).await,
+            Err(AppError::NotFound)
+        ));
+
+        let revived = service.retry(job.id, account_id).await.unwrap();
+        assert_eq!(revived.status, JobStatus::Queued);
+        assert_eq!(revived.attempts, 0);
+        assert_eq!(revived.last_error, None);
+        assert_eq!(revived.finished_at, None);
+        assert!(matches!(
+            service.retry(job.id, account_id).await,
+            Err(AppError::Conflict(_))
+        ));
+
+        let claimed = service.claim("w1").await.unwrap().unwrap();
+        assert_eq!(claimed.id, job.id);
+        assert_eq!(claimed.attempts, 1);
+    }
+}
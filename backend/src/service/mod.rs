@@ -1,2 +1,11 @@
 pub mod account;
+pub mod extraction;
+pub mod family;
+pub mod job;
+pub mod ocr;
+pub mod resource;
+pub mod session;
 pub mod task;
+pub mod task_draft;
+pub mod task_link;
+pub mod task_resource;
@@ -0,0 +1,138 @@
+use crate::app::context::Repos;
+use crate::error::AppError;
+use crate::model::resource::FileType;
+use crate::ocr::{clean_text, pdf, OcrEngine};
+use crate::storage::Storage;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 文字预提取（PRD 8.4）：图片走 OCR，PDF 提取内嵌文字，清洗后缓存到 resource.extracted_text
+#[derive(Clone)]
+pub struct OcrService {
+    repos: Repos,
+    storage: Arc<dyn Storage>,
+    engine: Option<Arc<dyn OcrEngine>>,
+}
+
+impl OcrService {
+    pub fn new(
+        repos: Repos,
+        storage: Arc<dyn Storage>,
+        engine: Option<Arc<dyn OcrEngine>>,
+    ) -> Self {
+        Self {
+            repos,
+            storage,
+            engine,
+        }
+    }
+
+    /// 提取资源中的文字（后台任务执行）；已提取过，或同一账户下相同内容的资源已提取过时直接复用，
+    /// 不重复识别。不支持的类型或未启用对应的提取方式时返回 None
+    pub async fn extract_text(
+        &self,
+        resource_id: Uuid,
+        pdf_text_extractor: Option<&str>,
+    ) -> Result<Option<String>, AppError> {
+        let resource = self.repos.resource.find_by_id(resource_id).await?;
+        if let Some(text) = resource.extracted_text {
+            return Ok(Some(text));
+        }
+        if let Some(hash) = &resource.content_hash {
+            let cached = self
+                .repos
+                .resource
+                .find_extracted_text(resource.account_id, hash)
+                .await?;
+            if let Some(text) = cached {
+                self.repos
+                    .resource
+                    .set_extracted_text(resource_id, &text)
+                    .await?;
+                return Ok(Some(text));
+            }
+        }
+
+        let raw = match (resource.file_type, &self.engine, pdf_text_extractor) {
+            (FileType::Image, Some(engine), _) => {
+                let data = self.storage.get(&resource.storage_key()).await?;
+                engine.recognize(&data).await?
+            }
+            (FileType::Pdf, _, Some(command)) => {
+                let data = self.storage.get(&resource.storage_key()).await?;
+                match pdf::extract_text(command, &data).await? {
+                    Some(raw) => raw,
+                    None => return Ok(None),
+                }
+            }
+            _ => return Ok(None),
+        };
+
+        // 没有文字时也缓存（空字符串），避免重复识别
+        let text = clean_text(&raw);
+        self.repos
+            .resource
+            .set_extracted_text(resource_id, &text)
+            .await?;
+        Ok(Some(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr::fake::FakeOcr;
+    use crate::test_support::{create_parent, create_resource, local_storage};
+    use sqlx::PgPool;
+
+    const HASH: &str = "hash-image";
+
+    async fn setup(pool: PgPool, text: &str) -> (OcrService, Arc<FakeOcr>, Repos, Uuid) {
+        let repos = Repos::new(pool);
+        let storage = local_storage().await;
+        let engine = Arc::new(FakeOcr::new(text));
+        let account_id = create_parent(&repos).await;
+        // 同一账户下相同内容共用一个存储对象
+        let key = crate::model::resource::blob_key(account_id, HASH);
+        storage.put(&key, b"fake image", "image/png").await.unwrap();
+        let service = OcrService::new(repos.clone(), storage, Some(engine.clone()));
+        (service, engine, repos, account_id)
+    }
+
+    #[sqlx::test]
+    async fn same_content_reuses_cached_text(pool: PgPool) {
+        let (service, engine, repos, account_id) = setup(pool, "  第1题\n\n\n第2题  ").await;
+        let first = create_resource(&repos, account_id, FileType::Image, HASH).await;
+        let second = create_resource(&repos, account_id, FileType::Image, HASH).await;
+
+        let text = service.extract_text(first.id, None).await.unwrap();
+        assert_eq!(text.as_deref(), Some("  第1题\n\n第2题"));
+        assert_eq!(engine.calls(), 1);
+
+        // 同一资源再次提取、相同内容的另一个资源提取，都不再识别
+        let again = service.extract_text(first.id, None).await.unwrap();
+        let reused = service.extract_text(second.id, None).await.unwrap();
+        assert_eq!(again, text);
+        assert_eq!(reused, text);
+        assert_eq!(engine.calls(), 1);
+
+        let stored = repos.resource.find_by_id(second.id).await.unwrap();
+        assert_eq!(stored.extracted_text, text);
+    }
+
+    #[sqlx::test]
+    async fn empty_text_counts_as_extracted(pool: PgPool) {
+        let (service, engine, repos, account_id) = setup(pool, " \n ").await;
+        let first = create_resource(&repos, account_id, FileType::Image, HASH).await;
+        let second = create_resource(&repos, account_id, FileType::Image, HASH).await;
+
+        let text = service.extract_text(first.id, None).await.unwrap();
+        assert_eq!(text.as_deref(), Some(""));
+
+        let again = service.extract_text(first.id, None).await.unwrap();
+        let reused = service.extract_text(second.id, None).await.unwrap();
+        assert_eq!(again.as_deref(), Some(""));
+        assert_eq!(reused.as_deref(), Some(""));
+        assert_eq!(engine.calls(), 1);
+    }
+}
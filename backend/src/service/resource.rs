@@ -0,0 +1,436 @@
+use crate::app::context::Repos;
+use crate::config::{AppConfig, OcrBackend};
+use crate::error::AppError;
+use crate::model::job::JobPayload;
+use crate::model::resource::{
+    blob_key, thumbnail_key, FileType, Resource, ResourceCreate, ResourceCursor, ResourceListQuery,
+    StorageUsageResponse, UploadFile,
+};
+use crate::storage::sniff::sniff;
+use crate::storage::{thumbnail, Storage};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 100;
+
+#[derive(Clone)]
+pub struct ResourceService {
+    repos: Repos,
+    storage: Arc<dyn Storage>,
+}
+
+impl ResourceService {
+    pub fn new(repos: Repos, storage: Arc<dyn Storage>) -> Self {
+        Self { repos, storage }
+    }
+
+    /// 上传资源：按文件头校验类型与大小，按 SHA-256 去重，同一账户已有相同内容时复用已存储的对象；
+    /// 先写存储再入库（含配额校验），入库失败时清理本次新写入的对象
+    pub async fn upload(
+        &self,
+        config: &AppConfig,
+        account_id: Uuid,
+        owner_id: Option<Uuid>,
+        file: UploadFile,
+    ) -> Result<Resource, AppError> {
+        // 家长可以为家庭中的孩子上传资料
+        let owner_id = owner_id.unwrap_or(account_id);
+        let account_ids = self.visible_account_ids(account_id).await?;
+        if !account_ids.contains(&owner_id) {
+            return Err(AppError::Forbidden);
+        }
+        if file.data.is_empty() {
+            return Err(AppError::BadRequest("上传的文件为空".into()));
+        }
+
+        let file_name = sanitize_file_name(&file.file_name);
+        let sniffed = sniff(&file_name, &file.data)?;
+        if !config.upload.is_allowed(sniffed.file_type) {
+            return Err(AppError::UnsupportedMediaType(sniffed.mime));
+        }
+        let max_bytes = config.upload.max_bytes(sniffed.file_type);
+        if file.data.len() > max_bytes {
+            return Err(AppError::PayloadTooLarge(max_bytes));
+        }
+        let content_hash = hex::encode(Sha256::digest(&file.data));
+        let file_size = file.data.len() as i64;
+        let key = blob_key(owner_id, &content_hash);
+
+        let existing_url = self
+            .repos
+            .resource
+            .find_blob_url(owner_id, &content_hash)
+            .await?;
+        let newly_stored = existing_url.is_none();
+        let storage_url = match existing_url {
+            Some(url) => url,
+            None => self.storage.put(&key, &file.data, &sniffed.mime).await?,
+        };
+
+        let now = chrono::Utc::now();
+        let created = self
+            .repos
+            .resource
+            .insert(
+                ResourceCreate {
+                    id: Uuid::new_v4(),
+                    account_id: owner_id,
+                    file_name,
+                    file_type: sniffed.file_type,
+                    storage_url,
+                    content_hash: content_hash.clone(),
+                    file_size,
+                    mime_type: sniffed.mime.clone(),
+                    created_at: now,
+                    created_by: account_id,
+                },
+                config.storage_quota_bytes,
+            )
+            .await;
+
+        // 复用的对象可能在入库前随最后一个引用被删除清理，入库后确认仍存在
+        if created.is_ok() && !newly_stored {
+            if let Err(AppError::NotFound) = self.storage.size(&key).await {
+                self.storage.put(&key, &file.data, &sniffed.mime).await?;
+            }
+        }
+
+        // 并发上传相同内容时对象可能已被其它资源引用，仍有引用则保留
+        if created.is_err() && newly_stored {
+            let referenced = self
+                .repos
+                .resource
+                .find_blob_url(owner_id, &content_hash)
+                .await
+                .map(|url| url.is_some())
+                .unwrap_or(true);
+            if !referenced {
+                if let Err(e) = self.storage.delete(&key).await {
+                    tracing::warn!(err = ?e, key, "清理未入库的对象失败");
+                }
+            }
+        }
+
+        let resource = created?;
+        self.enqueue_jobs(config, &resource, account_id).await;
+        Ok(resource)
+    }
+
+    // 图片和 PDF 入队生成缩略图、提取文字，入队失败只记录日志，不影响上传结果
+    async fn enqueue_jobs(&self, config: &AppConfig, resource: &Resource, account_id: Uuid) {
+        let extract_text = match resource.file_type {
+            FileType::Image => config.ocr.backend != OcrBackend::Disabled,
+            FileType::Pdf => config.pdf_text_extractor.is_some(),
+            _ => false,
+        };
+        let mut payloads = Vec::new();
+        if matches!(resource.file_type, FileType::Image | FileType::Pdf) {
+            payloads.push(JobPayload::Thumbnail {
+                resource_id: resource.id,
+            });
+        }
+        if extract_text {
+            payloads.push(JobPayload::ExtractText {
+                resource_id: resource.id,
+            });
+        }
+
+        for payload in payloads {
+            if let Err(e) = self.repos.job.enqueue(&payload, Some(account_id)).await {
+                tracing::warn!(err = ?e, resource_id = %resource.id, kind = payload.kind(), "后台任务入队失败");
+            }
+        }
+    }
+
+    /// 生成缩略图（后台任务执行），与原文件放在一起；相同内容的资源已生成过时直接复用
+    pub async fn generate_thumbnail(
+        &self,
+        resource_id: Uuid,
+        pdf_renderer: Option<&str>,
+    ) -> Result<(), AppError> {
+        let resource = self.repos.resource.find_by_id(resource_id).await?;
+        if !matches!(resource.file_type, FileType::Image | FileType::Pdf) {
+            return Ok(());
+        }
+
+        let key = thumbnail_key(&resource.storage_key());
+        if self.storage.size(&key).await.is_err() {
+            let data = self.storage.get(&resource.storage_key()).await?;
+            let Some(thumbnail) = thumbnail::render(resource.file_type, data, pdf_renderer).await?
+            else {
+                return Ok(());
+            };
+            self.storage.put(&key, &thumbnail, "image/jpeg").await?;
+        }
+        self.repos.resource.set_thumbnail(resource_id, &key).await
+    }
+
+    pub async fn get(&self, resource_id: Uuid, account_id: Uuid) -> Result<Resource, AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        self.repos
+            .resource
+            .find_by_id_and_account(resource_id, &account_ids)
+            .await
+    }
+
+    /// 条件查询 + 游标分页，返回当前页和下一页游标
+    pub async fn list(
+        &self,
+        account_id: Uuid,
+        query: &ResourceListQuery,
+    ) -> Result<(Vec<Resource>, Option<String>), AppError> {
+        let mut account_ids = self.visible_account_ids(account_id).await?;
+        if let Some(filter_id) = query.account_id {
+            if !account_ids.contains(&filter_id) {
+                return Err(AppError::Forbidden);
+            }
+            account_ids = vec![filter_id];
+        }
+
+        let cursor = match &query.cursor {
+            Some(raw) => Some(
+                ResourceCursor::decode(raw)
+                    .ok_or_else(|| AppError::BadRequest("无效的分页游标".into()))?,
+            ),
+            None => None,
+        };
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+
+        let mut resources = self
+            .repos
+            .resource
+            .search(
+                &account_ids,
+                query.file_type,
+                query.q.as_deref(),
+                cursor.as_ref(),
+                limit,
+            )
+            .await?;
+
+        let next_cursor = if resources.len() as i64 > limit {
+            resources.truncate(limit as usize);
+            resources.last().map(|r| {
+                ResourceCursor {
+                    created_at: r.created_at,
+                    id: r.id,
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
+
+        Ok((resources, next_cursor))
+    }
+
+    /// 账户存储空间使用情况，owner_id 为空时查询自己
+    pub async fn usage(
+        &self,
+        config: &AppConfig,
+        account_id: Uuid,
+        owner_id: Option<Uuid>,
+    ) -> Result<StorageUsageResponse, AppError> {
+        let owner_id = owner_id.unwrap_or(account_id);
+        let account_ids = self.visible_account_ids(account_id).await?;
+        if !account_ids.contains(&owner_id) {
+            return Err(AppError::Forbidden);
+        }
+
+        Ok(StorageUsageResponse {
+            used_bytes: self.repos.resource.usage(owner_id).await?,
+            quota_bytes: config.storage_quota_bytes,
+        })
+    }
+
+    /// 下载：返回资源信息和文件内容
+    pub async fn download(
+        &self,
+        resource_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<(Resource, Vec<u8>), AppError> {
+        let resource = self.get(resource_id, account_id).await?;
+        let data = self.storage.get(&resource.storage_key()).await?;
+        Ok((resource, data))
+    }
+
+    /// 签名链接下载：签名已由调用方校验，这里只取资源和文件大小
+    pub async fn open_signed(&self, resource_id: Uuid) -> Result<(Resource, u64), AppError> {
+        let resource = self.repos.resource.find_by_id(resource_id).await?;
+        let size = self.storage.size(&resource.storage_key()).await?;
+        Ok((resource, size))
+    }
+
+    /// 签名链接读取缩略图，尚未生成时返回 404
+    pub async fn open_thumbnail(&self, resource_id: Uuid) -> Result<Vec<u8>, AppError> {
+        let resource = self.repos.resource.find_by_id(resource_id).await?;
+        let key = resource.thumbnail_key.ok_or(AppError::NotFound)?;
+        self.storage.get(&key).await
+    }
+
+    /// 读取文件内容，range 为闭区间 [start, end]
+    pub async fn read(
+        &self,
+        resource: &Resource,
+        range: Option<(u64, u64)>,
+    ) -> Result<Vec<u8>, AppError> {
+        let key = resource.storage_key();
+        match range {
+            Some((start, end)) => self.storage.get_range(&key, start, end).await,
+            None => self.storage.get(&key).await,
+        }
+    }
+
+    /// 软删除：标记删除，同一内容已无其它资源引用时清理存储中的文件和缩略图
+    pub async fn delete(&self, resource_id: Uuid, account_id: Uuid) -> Result<(), AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        self.repos
+            .resource
+            .delete(
+                resource_id,
+                &account_ids,
+                account_id,
+                |resource| async move {
+                    // 缩略图与原文件按同一内容共用，一并清理；失败只留下孤立对象，不影响删除结果
+                    let key = resource.storage_key();
+                    for key in [thumbnail_key(&key), key] {
+                        if let Err(e) = self.storage.delete(&key).await {
+                            tracing::warn!(err = ?e, key, "清理已删除资源的对象失败");
+                        }
+                    }
+                },
+            )
+            .await
+    }
+
+    async fn visible_account_ids(&self, account_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        self.repos.account.visible_account_ids(account_id).await
+    }
+}
+
+// 去掉客户端可能带上的路径，只保留文件名
+fn sanitize_file_name(raw: &str) -> String {
+    let name = raw.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+    if name.is_empty() {
+        "未命名文件".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{config, create_parent, local_storage};
+    use salvo::http::StatusCode;
+    use salvo::{Depot, Request, Response, Writer};
+    use sqlx::PgPool;
+
+    fn text_file(content: &str) -> UploadFile {
+        UploadFile {
+            file_name: "笔记.txt".into(),
+            data: content.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn file_name_drops_client_path() {
+        assert_eq!(sanitize_file_name("作业.pdf"), "作业.pdf");
+        assert_eq!(sanitize_file_name("/home/kid/作业.pdf"), "作业.pdf");
+        assert_eq!(sanitize_file_name(r"C:\Users\kid\作业.pdf"), "作业.pdf");
+        assert_eq!(sanitize_file_name("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_file_name("  spaced.png  "), "spaced.png");
+    }
+
+    #[test]
+    fn empty_file_name_gets_placeholder() {
+        for raw in ["", "   ", "dir/", r"dir\"] {
+            assert_eq!(sanitize_file_name(raw), "未命名文件", "{:?}", raw);
+        }
+    }
+
+    #[sqlx::test]
+    async fn same_content_shares_one_blob(pool: PgPool) {
+        let config = config();
+        let repos = Repos::new(pool);
+        let storage = local_storage().await;
+        let service = ResourceService::new(repos.clone(), storage.clone());
+        let account_id = create_parent(&repos).await;
+
+        let first = service
+            .upload(&config, account_id, None, text_file("九九乘法表"))
+            .await
+            .unwrap();
+        let second = service
+            .upload(&config, account_id, None, text_file("九九乘法表"))
+            .await
+            .unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.storage_key(), second.storage_key());
+        assert_eq!(first.storage_url, second.storage_url);
+        // 相同内容只计一次
+        let usage = service.usage(&config, account_id, None).await.unwrap();
+        assert_eq!(usage.used_bytes, first.file_size);
+
+        // 删除其中一条，对象仍被另一条引用
+        service.delete(first.id, account_id).await.unwrap();
+        assert!(storage.size(&second.storage_key()).await.is_ok());
+        let (_, data) = service.download(second.id, account_id).await.unwrap();
+        assert_eq!(data, "九九乘法表".as_bytes());
+
+        // 删除最后一条引用，对象随之清理
+        service.delete(second.id, account_id).await.unwrap();
+        assert!(matches!(
+            storage.size(&second.storage_key()).await,
+            Err(AppError::NotFound)
+        ));
+        let usage = service.usage(&config, account_id, None).await.unwrap();
+        assert_eq!(usage.used_bytes, 0);
+    }
+
+    #[sqlx::test]
+    async fn upload_over_quota_is_rejected(pool: PgPool) {
+        let mut config = config();
+        config.storage_quota_bytes = 10;
+        let repos = Repos::new(pool);
+        let storage = local_storage().await;
+        let service = ResourceService::new(repos.clone(), storage.clone());
+        let account_id = create_parent(&repos).await;
+
+        service
+            .upload(&config, account_id, None, text_file("0123456789"))
+            .await
+            .unwrap();
+        // 重复内容不占用额外空间，满额时仍可上传
+        service
+            .upload(&config, account_id, None, text_file("0123456789"))
+            .await
+            .unwrap();
+
+        let err = service
+            .upload(&config, account_id, None, text_file("a"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::QuotaExceeded {
+                used: 10,
+                requested: 1,
+                quota: 10
+            }
+        ));
+        // 未入库的新对象已清理
+        let key = blob_key(account_id, &hex::encode(Sha256::digest(b"a")));
+        assert!(matches!(storage.size(&key).await, Err(AppError::NotFound)));
+
+        let mut res = Response::new();
+        err.write(&mut Request::new(), &mut Depot::new(), &mut res)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+}
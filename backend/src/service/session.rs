@@ -0,0 +1,236 @@
+use crate::app::context::Repos;
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::model::account::{Account, Claims};
+use crate::model::session::IssuedTokens;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{EncodingKey, Header};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct SessionService {
+    repos: Repos,
+}
+
+impl SessionService {
+    pub fn new(repos: Repos) -> Self {
+        Self { repos }
+    }
+
+    /// 登录成功后开启新会话
+    pub async fn start(
+        &self,
+        config: &AppConfig,
+        account: &Account,
+    ) -> Result<IssuedTokens, AppError> {
+        let session_id = Uuid::new_v4();
+        let expires_at = Utc::now() + Duration::days(config.jwt_refresh_expiration_days);
+        self.repos
+            .session
+            .insert_session(session_id, account.id, &expires_at)
+            .await?;
+
+        self.issue(config, account, session_id, &expires_at).await
+    }
+
+    /// 轮换 refresh token：旧 token 作废，签发新的一对
+    pub async fn refresh(
+        &self,
+        config: &AppConfig,
+        refresh_token: &str,
+    ) -> Result<IssuedTokens, AppError> {
+        let token = self
+            .repos
+            .session
+            .find_refresh_token(&hash_token(refresh_token))
+            .await
+            .map_err(not_found_as_unauthorized)?;
+        let session = self
+            .repos
+            .session
+            .find_session(token.session_id)
+            .await
+            .map_err(not_found_as_unauthorized)?;
+
+        // 已轮换过的 token 再次出现，说明可能被盗用：吊销整个令牌家族
+        if token.used_at.is_some() {
+            return Err(self.revoke_on_reuse(session.id, session.account_id).await);
+        }
+
+        let now = Utc::now();
+        if !session.is_active(now) || token.expires_at <= now {
+            return Err(AppError::Unauthorized);
+        }
+
+        // 并发请求同时使用同一个 token，只有一个能成功
+        if !self.repos.session.mark_refresh_token_used(token.id).await? {
+            return Err(self.revoke_on_reuse(session.id, session.account_id).await);
+        }
+
+        // 重新读取账户，角色变更或账户删除在刷新时生效
+        let account = self
+            .repos
+            .account
+            .get_by_id(session.account_id)
+            .await
+            .map_err(not_found_as_unauthorized)?;
+
+        self.issue(config, &account, session.id, &session.expires_at)
+            .await
+    }
+
+    /// 注销：吊销当前会话，该会话下的 access / refresh token 全部失效
+    pub async fn revoke(&self, session_id: Uuid, account_id: Uuid) -> Result<(), AppError> {
+        self.repos
+            .session
+            .revoke_session(session_id, account_id)
+            .await
+    }
+
+    pub async fn is_active(&self, session_id: Uuid) -> Result<bool, AppError> {
+        match self.repos.session.find_session(session_id).await {
+            Ok(session) => Ok(session.is_active(Utc::now())),
+            Err(AppError::NotFound) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn issue(
+        &self,
+        config: &AppConfig,
+        account: &Account,
+        session_id: Uuid,
+        expires_at: &DateTime<Utc>,
+    ) -> Result<IssuedTokens, AppError> {
+        let refresh_token = generate_refresh_token();
+        self.repos
+            .session
+            .insert_refresh_token(session_id, &hash_token(&refresh_token), expires_at)
+            .await?;
+
+        let expires_in = config.jwt_access_expiration_minutes * 60;
+        let claims = Claims {
+            sub: account.id.to_string(),
+            sid: session_id.to_string(),
+            role: account.role()?,
+            fid: account.family_id.to_string(),
+            exp: (Utc::now() + Duration::seconds(expires_in)).timestamp() as usize,
+        };
+        let access_token = jsonwebtoken::encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
+        )
+        .map_err(|e| {
+            tracing::error!(err = ?e, "JWT 签发失败");
+            AppError::Internal
+        })?;
+
+        Ok(IssuedTokens {
+            access_token,
+            refresh_token,
+            expires_in,
+        })
+    }
+
+    async fn revoke_on_reuse(&self, session_id: Uuid, account_id: Uuid) -> AppError {
+        tracing::warn!(%session_id, %account_id, "检测到 refresh token 复用，吊销会话");
+        match self.revoke(session_id, account_id).await {
+            Ok(()) => AppError::Unauthorized,
+            Err(e) => e,
+        }
+    }
+}
+
+fn generate_refresh_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn not_found_as_unauthorized(err: AppError) -> AppError {
+    match err {
+        AppError::NotFound => AppError::Unauthorized,
+        e => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{config, create_parent};
+    use sqlx::PgPool;
+
+    async fn start(pool: PgPool) -> (SessionService, AppConfig, IssuedTokens) {
+        let repos = Repos::new(pool);
+        let account_id = create_parent(&repos).await;
+        let account = repos.account.get_by_id(account_id).await.unwrap();
+        let service = SessionService::new(repos);
+        let config = config();
+        let tokens = service.start(&config, &account).await.unwrap();
+        (service, config, tokens)
+    }
+
+    fn session_id(config: &AppConfig, tokens: &IssuedTokens) -> Uuid {
+        let claims = jsonwebtoken::decode::<Claims>(
+            &tokens.access_token,
+            &jsonwebtoken::DecodingKey::from_secret(config.jwt_secret.as_bytes()),
+            &jsonwebtoken::Validation::default(),
+        )
+        .unwrap()
+        .claims;
+        Uuid::parse_str(&claims.sid).unwrap()
+    }
+
+    #[sqlx::test]
+    async fn rotated_token_is_single_use(pool: PgPool) {
+        let (service, config, first) = start(pool).await;
+
+        let second = service
+            .refresh(&config, &first.refresh_token)
+            .await
+            .unwrap();
+        assert_ne!(second.refresh_token, first.refresh_token);
+        assert_eq!(session_id(&config, &second), session_id(&config, &first));
+
+        // 轮换得到的新 token 可继续轮换，每次签发新的
+        let third = service
+            .refresh(&config, &second.refresh_token)
+            .await
+            .unwrap();
+        assert_ne!(third.refresh_token, second.refresh_token);
+
+        assert!(matches!(
+            service.refresh(&config, "unknown-token").await,
+            Err(AppError::Unauthorized)
+        ));
+    }
+
+    #[sqlx::test]
+    async fn reused_token_revokes_whole_family(pool: PgPool) {
+        let (service, config, first) = start(pool).await;
+        let session_id = session_id(&config, &first);
+        let second = service
+            .refresh(&config, &first.refresh_token)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service.refresh(&config, &first.refresh_token).await,
+            Err(AppError::Unauthorized)
+        ));
+
+        // 合法持有者手上的最新 token 也随会话一起失效
+        assert!(!service.is_active(session_id).await.unwrap());
+        assert!(matches!(
+            service.refresh(&config, &second.refresh_token).await,
+            Err(AppError::Unauthorized)
+        ));
+    }
+}
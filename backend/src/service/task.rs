@@ -1,8 +1,16 @@
 use crate::app::context::Repos;
 use crate::error::AppError;
-use crate::model::task::{CreateTaskRequest, Task, UpdateTaskRequest};
+use crate::model::task::{
+    CreateTaskRequest, PatchTaskRequest, PlanProgress, Task, TaskCreate, TaskCursor, TaskListQuery,
+    TaskResponse, TaskSource, TaskStatus, TaskTransition, TaskType, UpdateTaskRequest,
+};
+use crate::model::task_link::LinkType;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 100;
+
 #[derive(Clone)]
 pub struct TaskService {
     repos: Repos,
@@ -18,36 +26,608 @@ impl TaskService {
         account_id: Uuid,
         input: &CreateTaskRequest,
     ) -> Result<Task, AppError> {
-        let uid = uuid::Uuid::new_v4();
-        let now = chrono::Utc::now();
+        // 家长可以为家庭中的孩子创建任务
+        let owner_id = input.account_id.unwrap_or(account_id);
+        let account_ids = self.visible_account_ids(account_id).await?;
+        if !account_ids.contains(&owner_id) {
+            return Err(AppError::Forbidden);
+        }
 
         self.repos
             .task
-            .insert(uid, account_id, &now, &now, input)
+            .insert(TaskCreate {
+                id: uuid::Uuid::new_v4(),
+                account_id: owner_id,
+                title: input.title.clone(),
+                description: input.description.clone(),
+                task_type: input.task_type,
+                subject: input.subject,
+                due_date: input.due_date,
+                auto_complete: input.auto_complete,
+                source: TaskSource::Manual,
+                created_at: chrono::Utc::now(),
+                created_by: account_id,
+            })
             .await
     }
 
     pub async fn get(&self, task_id: Uuid, account_id: Uuid) -> Result<Task, AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
         self.repos
             .task
-            .find_by_id_and_account(task_id, account_id)
+            .find_by_id_and_account(task_id, &account_ids)
             .await
     }
 
-    pub async fn list(&self, account_id: Uuid) -> Result<Vec<Task>, AppError> {
-        self.repos.task.find_all_by_account(account_id).await
+    /// 条件查询 + 游标分页，返回当前页和下一页游标
+    pub async fn list(
+        &self,
+        account_id: Uuid,
+        query: &TaskListQuery,
+    ) -> Result<(Vec<Task>, Option<String>), AppError> {
+        let mut account_ids = self.visible_account_ids(account_id).await?;
+        if let Some(filter_id) = query.account_id {
+            if !account_ids.contains(&filter_id) {
+                return Err(AppError::Forbidden);
+            }
+            account_ids = vec![filter_id];
+        }
+
+        let cursor = match &query.cursor {
+            Some(raw) => Some(
+                TaskCursor::decode(raw, query.sort, query.order)
+                    .ok_or_else(|| AppError::BadRequest("无效的分页游标".into()))?,
+            ),
+            None => None,
+        };
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+
+        let mut tasks = self
+            .repos
+            .task
+            .search(&account_ids, query, cursor.as_ref(), limit)
+            .await?;
+
+        let next_cursor = if tasks.len() as i64 > limit {
+            tasks.truncate(limit as usize);
+            tasks
+                .last()
+                .map(|t| TaskCursor::from_task(query.sort, query.order, t).encode())
+        } else {
+            None
+        };
+
+        Ok((tasks, next_cursor))
     }
 
+    /// PUT：未提供的字段保持不变
     pub async fn update(
         &self,
         task_id: Uuid,
         account_id: Uuid,
         input: UpdateTaskRequest,
-    ) -> Result<(), AppError> {
-        self.repos.task.update(task_id, account_id, input).await
+    ) -> Result<Task, AppError> {
+        self.patch(task_id, account_id, input.into()).await
+    }
+
+    /// PATCH：显式 null 清空可空字段（description / due_date）
+    pub async fn patch(
+        &self,
+        task_id: Uuid,
+        account_id: Uuid,
+        input: PatchTaskRequest,
+    ) -> Result<Task, AppError> {
+        for (field, is_null) in [
+            ("title", input.title.is_null()),
+            ("task_type", input.task_type.is_null()),
+            ("subject", input.subject.is_null()),
+            ("auto_complete", input.auto_complete.is_null()),
+        ] {
+            if is_null {
+                return Err(AppError::BadRequest(format!("字段 {} 不能为空", field)));
+            }
+        }
+
+        let account_ids = self.visible_account_ids(account_id).await?;
+        let task = self
+            .repos
+            .task
+            .patch(task_id, &account_ids, account_id, &input)
+            .await?;
+
+        // 为子任务已全部完成的计划开启 auto_complete 时立即完成
+        if input.auto_complete.value() == Some(&true) && task.task_type == TaskType::Plan {
+            auto_complete_plans(&self.repos, vec![task.clone()], account_id).await?;
+            return self
+                .repos
+                .task
+                .find_by_id_and_account(task.id, &account_ids)
+                .await;
+        }
+        Ok(task)
+    }
+
+    /// 状态流转（完成 / 重新打开 / 归档 / 取消归档），由状态机校验是否允许；
+    /// 完成时默认要求前置任务已全部完成，force 为 true 时跳过该检查
+    pub async fn transition(
+        &self,
+        task_id: Uuid,
+        account_id: Uuid,
+        transition: TaskTransition,
+        force: bool,
+    ) -> Result<Task, AppError> {
+        let task = self.get(task_id, account_id).await?;
+        let to = transition.target(&task).ok_or_else(|| {
+            AppError::Conflict(format!(
+                "任务当前状态为 {}，无法{}",
+                task.status.as_str(),
+                transition.label()
+            ))
+        })?;
+        if transition == TaskTransition::Complete
+            && !force
+            && !self
+                .repos
+                .task_link
+                .find_blocked(&[task.id])
+                .await?
+                .is_empty()
+        {
+            return Err(AppError::Conflict("存在未完成的前置任务，无法完成".into()));
+        }
+        let completed_at = transition.completed_at(&task, chrono::Utc::now());
+
+        let task = self
+            .repos
+            .task
+            .transition(task.id, task.status, to, completed_at, account_id)
+            .await?;
+
+        // 完成、重新打开、归档、取消归档都会改变所属计划的进度
+        let parents = self.includes_parents(&task).await?;
+        auto_complete_plans(&self.repos, parents, account_id).await?;
+        Ok(task)
     }
 
     pub async fn delete(&self, task_id: Uuid, account_id: Uuid) -> Result<(), AppError> {
-        self.repos.task.delete(task_id, account_id).await
+        let task = self.get(task_id, account_id).await?;
+        let account_ids = self.visible_account_ids(account_id).await?;
+        self.repos
+            .task
+            .delete(task_id, &account_ids, account_id)
+            .await?;
+
+        let parents = self.includes_parents(&task).await?;
+        auto_complete_plans(&self.repos, parents, account_id).await
+    }
+
+    /// 转换为响应并批量填充 blocked / progress
+    pub async fn to_responses(&self, tasks: Vec<Task>) -> Result<Vec<TaskResponse>, AppError> {
+        let annotations = TaskAnnotations::load(&self.repos, &tasks).await?;
+        Ok(tasks.into_iter().map(|t| annotations.response(t)).collect())
+    }
+
+    pub async fn to_response(&self, task: Task) -> Result<TaskResponse, AppError> {
+        let mut responses = self.to_responses(vec![task]).await?;
+        Ok(responses.remove(0))
+    }
+
+    /// 通过 includes 直接包含该任务的计划
+    async fn includes_parents(&self, task: &Task) -> Result<Vec<Task>, AppError> {
+        let parents = self
+            .repos
+            .task_link
+            .find_parents(task.id, &[task.account_id], Some(LinkType::Includes))
+            .await?;
+        Ok(parents.into_iter().map(|p| p.task).collect())
+    }
+
+    /// 当前账户可访问其任务的账户集合（家长含家庭中的孩子）
+    async fn visible_account_ids(&self, account_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        self.repos.account.visible_account_ids(account_id).await
+    }
+}
+
+/// 检查给定计划及其上层计划：开启 auto_complete 且子任务全部完成的计划自动完成。
+/// 子任务状态或计划的包含关系变化后调用；进度按叶子任务递归统计，
+/// 因此无论中间计划是否完成都要一直检查到最上层
+pub(crate) async fn auto_complete_plans(
+    repos: &Repos,
+    mut pending: Vec<Task>,
+    actor_id: Uuid,
+) -> Result<(), AppError> {
+    let mut visited = HashSet::new();
+
+    while let Some(plan) = pending.pop() {
+        if !visited.insert(plan.id) {
+            continue;
+        }
+
+        if plan.task_type == TaskType::Plan
+            && plan.auto_complete
+            && plan.status == TaskStatus::Active
+        {
+            let now = chrono::Utc::now();
+            let progress = repos.task_link.find_plan_progress(&[plan.id], &now).await?;
+            let all_done = progress
+                .first()
+                .is_some_and(|(_, p)| p.total > 0 && p.done == p.total);
+            if all_done && repos.task_link.find_blocked(&[plan.id]).await?.is_empty() {
+                match repos
+                    .task
+                    .transition(
+                        plan.id,
+                        TaskStatus::Active,
+                        TaskStatus::Done,
+                        Some(now),
+                        actor_id,
+                    )
+                    .await
+                {
+                    Ok(_) => {}
+                    // 计划已被其它请求修改状态，跳过
+                    Err(AppError::Conflict(_)) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        let parents = repos
+            .task_link
+            .find_parents(plan.id, &[plan.account_id], Some(LinkType::Includes))
+            .await?;
+        pending.extend(parents.into_iter().map(|p| p.task));
+    }
+
+    Ok(())
+}
+
+/// 响应中的计算字段（blocked / progress），按一批任务一次性查询
+pub(crate) struct TaskAnnotations {
+    blocked: HashSet<Uuid>,
+    progress: HashMap<Uuid, PlanProgress>,
+}
+
+impl TaskAnnotations {
+    pub(crate) async fn load<'a>(
+        repos: &Repos,
+        tasks: impl IntoIterator<Item = &'a Task>,
+    ) -> Result<Self, AppError> {
+        let mut ids = Vec::new();
+        let mut plan_ids = Vec::new();
+        for task in tasks {
+            ids.push(task.id);
+            if task.task_type == TaskType::Plan {
+                plan_ids.push(task.id);
+            }
+        }
+
+        let blocked = repos.task_link.find_blocked(&ids).await?;
+        let progress = repos
+            .task_link
+            .find_plan_progress(&plan_ids, &chrono::Utc::now())
+            .await?;
+
+        Ok(Self {
+            blocked: blocked.into_iter().collect(),
+            progress: progress.into_iter().collect(),
+        })
+    }
+
+    pub(crate) fn response(&self, task: Task) -> TaskResponse {
+        let blocked = self.blocked.contains(&task.id);
+        let progress = (task.task_type == TaskType::Plan)
+            .then(|| self.progress.get(&task.id).copied().unwrap_or_default());
+
+        let mut response: TaskResponse = task.into();
+        response.blocked = blocked;
+        response.progress = progress;
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::task::Subject;
+    use crate::model::task_link::CreateTaskLinkRequest;
+    use crate::service::task_link::TaskLinkService;
+    use crate::test_support::{create_parent, create_plan, create_task};
+    use chrono::{DateTime, Duration, Utc};
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    async fn task_due(repos: &Repos, account_id: Uuid, due_date: Option<DateTime<Utc>>) -> Task {
+        repos
+            .task
+            .insert(TaskCreate {
+                id: Uuid::new_v4(),
+                account_id,
+                title: "作业".into(),
+                description: None,
+                task_type: TaskType::Homework,
+                subject: Subject::Math,
+                due_date,
+                auto_complete: false,
+                source: TaskSource::Manual,
+                created_at: Utc::now(),
+                created_by: account_id,
+            })
+            .await
+            .unwrap()
+    }
+
+    // 按 limit 逐页读取，返回全部任务 ID
+    async fn all_pages(service: &TaskService, account_id: Uuid, order: &str) -> Vec<Uuid> {
+        let mut ids = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let query = serde_json::from_value(json!({
+                "sort": "due_date",
+                "order": order,
+                "limit": 2,
+                "cursor": cursor,
+            }))
+            .unwrap();
+            let (tasks, next) = service.list(account_id, &query).await.unwrap();
+            assert!(tasks.len() <= 2);
+            ids.extend(tasks.iter().map(|t| t.id));
+            match next {
+                Some(next) => cursor = Some(next),
+                None => return ids,
+            }
+        }
+    }
+
+    #[sqlx::test]
+    async fn due_date_pages_in_both_directions(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = TaskService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let base = Utc::now();
+        let day = |n: i64| Some(base + Duration::days(n));
+
+        let first = task_due(&repos, account_id, day(1)).await.id;
+        let last = task_due(&repos, account_id, day(3)).await.id;
+        // 同一截止时间按 id 排序
+        let mut same_day = vec![
+            task_due(&repos, account_id, day(2)).await.id,
+            task_due(&repos, account_id, day(2)).await.id,
+        ];
+        same_day.sort();
+        // 没有截止时间的任务无论升序降序都排在最后，彼此按 id 排序
+        let mut no_due = Vec::new();
+        for _ in 0..3 {
+            no_due.push(task_due(&repos, account_id, None).await.id);
+        }
+        no_due.sort();
+
+        let mut asc = vec![first];
+        asc.extend(&same_day);
+        asc.push(last);
+        asc.extend(&no_due);
+        assert_eq!(all_pages(&service, account_id, "asc").await, asc);
+
+        let mut desc = vec![last];
+        desc.extend(same_day.iter().rev());
+        desc.push(first);
+        desc.extend(no_due.iter().rev());
+        assert_eq!(all_pages(&service, account_id, "desc").await, desc);
+    }
+
+    #[sqlx::test]
+    async fn cursor_must_match_sort_and_order(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = TaskService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        for n in 0..3 {
+            task_due(&repos, account_id, Some(Utc::now() + Duration::days(n))).await;
+        }
+        let query = |sort: &str, order: &str, cursor: Option<&str>| -> TaskListQuery {
+            serde_json::from_value(json!({
+                "sort": sort,
+                "order": order,
+                "limit": 1,
+                "cursor": cursor,
+            }))
+            .unwrap()
+        };
+
+        let (_, next) = service
+            .list(account_id, &query("due_date", "asc", None))
+            .await
+            .unwrap();
+        let next = next.unwrap();
+        assert!(service
+            .list(account_id, &query("due_date", "asc", Some(&next)))
+            .await
+            .is_ok());
+        for (sort, order) in [("due_date", "desc"), ("created_at", "asc")] {
+            assert!(matches!(
+                service
+                    .list(account_id, &query(sort, order, Some(&next)))
+                    .await,
+                Err(AppError::BadRequest(_))
+            ));
+        }
+    }
+
+    #[sqlx::test]
+    async fn patch_distinguishes_missing_null_and_value(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = TaskService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let task = task_due(&repos, account_id, Some(Utc::now())).await;
+        let patch =
+            |body: serde_json::Value| -> PatchTaskRequest { serde_json::from_value(body).unwrap() };
+
+        let patched = service
+            .patch(
+                task.id,
+                account_id,
+                patch(json!({ "description": "第 1-3 题" })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(patched.description.as_deref(), Some("第 1-3 题"));
+        assert_eq!(patched.title, task.title);
+        assert_eq!(patched.due_date, task.due_date);
+
+        // 缺省字段保持不变，null 清空
+        let patched = service
+            .patch(task.id, account_id, patch(json!({ "due_date": null })))
+            .await
+            .unwrap();
+        assert_eq!(patched.due_date, None);
+        assert_eq!(patched.description.as_deref(), Some("第 1-3 题"));
+
+        let patched = service
+            .patch(
+                task.id,
+                account_id,
+                patch(json!({ "title": "口算", "description": null })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(patched.title, "口算");
+        assert_eq!(patched.description, None);
+
+        // 必填字段不能清空
+        assert!(matches!(
+            service
+                .patch(task.id, account_id, patch(json!({ "title": null })))
+                .await,
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    async fn include(repos: &Repos, plan: &Task, child: &Task) {
+        let input = CreateTaskLinkRequest {
+            child_task_id: child.id,
+            link_type: LinkType::Includes,
+        };
+        TaskLinkService::new(repos.clone())
+            .attach(plan.id, plan.account_id, &input)
+            .await
+            .unwrap();
+    }
+
+    async fn apply(service: &TaskService, task: &Task, transition: TaskTransition) {
+        service
+            .transition(task.id, task.account_id, transition, false)
+            .await
+            .unwrap();
+    }
+
+    async fn status(service: &TaskService, task: &Task) -> TaskStatus {
+        service.get(task.id, task.account_id).await.unwrap().status
+    }
+
+    #[sqlx::test]
+    async fn plan_progress_counts_nested_leaves(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = TaskService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let now = Utc::now();
+        let plan = create_plan(&repos, account_id, false).await;
+        let sub_plan = create_plan(&repos, account_id, false).await;
+        let done = task_due(&repos, account_id, None).await;
+        let overdue = task_due(&repos, account_id, Some(now - Duration::days(1))).await;
+        let upcoming = task_due(&repos, account_id, Some(now + Duration::days(1))).await;
+        let archived = task_due(&repos, account_id, Some(now - Duration::days(1))).await;
+        let no_due = task_due(&repos, account_id, None).await;
+
+        include(&repos, &plan, &done).await;
+        include(&repos, &plan, &overdue).await;
+        include(&repos, &plan, &sub_plan).await;
+        include(&repos, &sub_plan, &upcoming).await;
+        include(&repos, &sub_plan, &archived).await;
+        include(&repos, &sub_plan, &no_due).await;
+        // 同时被上下两层计划包含的任务只计一次
+        include(&repos, &plan, &upcoming).await;
+        apply(&service, &done, TaskTransition::Complete).await;
+        apply(&service, &archived, TaskTransition::Archive).await;
+
+        let responses = service
+            .to_responses(vec![plan.clone(), sub_plan.clone(), done.clone()])
+            .await
+            .unwrap();
+        let progress = responses[0].progress.unwrap();
+        // 嵌套计划本身不计入，已归档的任务不计入
+        assert_eq!(
+            (
+                progress.total,
+                progress.done,
+                progress.active,
+                progress.overdue
+            ),
+            (4, 1, 3, 1)
+        );
+        let progress = responses[1].progress.unwrap();
+        assert_eq!(
+            (
+                progress.total,
+                progress.done,
+                progress.active,
+                progress.overdue
+            ),
+            (2, 0, 2, 0)
+        );
+        assert!(responses[2].progress.is_none());
+
+        // 空计划进度为 0
+        let empty = create_plan(&repos, account_id, false).await;
+        let progress = service.to_response(empty).await.unwrap().progress.unwrap();
+        assert_eq!(progress.total, 0);
+    }
+
+    #[sqlx::test]
+    async fn nested_plans_auto_complete_bottom_up(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = TaskService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let plan = create_plan(&repos, account_id, true).await;
+        let sub_plan = create_plan(&repos, account_id, true).await;
+        let first = create_task(&repos, account_id, "第一课").await;
+        let second = create_task(&repos, account_id, "第二课").await;
+        let review = create_task(&repos, account_id, "复习").await;
+        include(&repos, &plan, &sub_plan).await;
+        include(&repos, &sub_plan, &first).await;
+        include(&repos, &sub_plan, &second).await;
+        include(&repos, &plan, &review).await;
+
+        apply(&service, &first, TaskTransition::Complete).await;
+        assert_eq!(status(&service, &sub_plan).await, TaskStatus::Active);
+
+        apply(&service, &second, TaskTransition::Complete).await;
+        assert_eq!(status(&service, &sub_plan).await, TaskStatus::Done);
+        assert_eq!(status(&service, &plan).await, TaskStatus::Active);
+
+        // 归档剩下的任务后计划不再有未完成的任务
+        apply(&service, &review, TaskTransition::Archive).await;
+        assert_eq!(status(&service, &plan).await, TaskStatus::Done);
+    }
+
+    #[sqlx::test]
+    async fn plan_without_auto_complete_stays_open(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = TaskService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let plan = create_plan(&repos, account_id, false).await;
+        let task = create_task(&repos, account_id, "背单词").await;
+        include(&repos, &plan, &task).await;
+
+        apply(&service, &task, TaskTransition::Complete).await;
+        assert_eq!(status(&service, &plan).await, TaskStatus::Active);
+
+        // 子任务已全部完成时开启 auto_complete，立即完成
+        let patch: PatchTaskRequest =
+            serde_json::from_value(json!({ "auto_complete": true })).unwrap();
+        let patched = service.patch(plan.id, account_id, patch).await.unwrap();
+        assert_eq!(patched.status, TaskStatus::Done);
+        assert!(patched.completed_at.is_some());
     }
 }
@@ -0,0 +1,427 @@
+use crate::app::context::Repos;
+use crate::error::AppError;
+use crate::model::task::Task;
+use crate::model::task_draft::{DraftStatus, PatchTaskDraftRequest, TaskDraft, TaskDraftListQuery};
+use crate::model::task_link::LinkType;
+use crate::repository::task_link::TaskLinkRepository;
+use sqlx::PgConnection;
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+
+// 单次批量确认 / 拒绝的上限
+const MAX_BATCH_SIZE: usize = 50;
+
+#[derive(Clone)]
+pub struct TaskDraftService {
+    repos: Repos,
+}
+
+impl TaskDraftService {
+    pub fn new(repos: Repos) -> Self {
+        Self { repos }
+    }
+
+    pub async fn list(
+        &self,
+        account_id: Uuid,
+        query: &TaskDraftListQuery,
+    ) -> Result<Vec<TaskDraft>, AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        self.repos
+            .task_draft
+            .find(
+                &account_ids,
+                query.resource_id,
+                query.status.unwrap_or(DraftStatus::Pending),
+            )
+            .await
+    }
+
+    /// 编辑待确认的草稿，已确认或已拒绝的草稿返回 409
+    pub async fn patch(
+        &self,
+        draft_id: Uuid,
+        account_id: Uuid,
+        input: PatchTaskDraftRequest,
+    ) -> Result<TaskDraft, AppError> {
+        for (field, is_null) in [
+            ("title", input.title.is_null()),
+            ("task_type", input.task_type.is_null()),
+            ("subject", input.subject.is_null()),
+        ] {
+            if is_null {
+                return Err(AppError::BadRequest(format!("字段 {} 不能为空", field)));
+            }
+        }
+        if input.title.value().is_some_and(|t| t.trim().is_empty()) {
+            return Err(AppError::BadRequest("字段 title 不能为空".into()));
+        }
+
+        let account_ids = self.visible_account_ids(account_id).await?;
+        let draft = self
+            .repos
+            .task_draft
+            .find_by_id_and_account(draft_id, &account_ids)
+            .await?;
+        if draft.status != DraftStatus::Pending {
+            return Err(AppError::Conflict("草稿已处理，不能再编辑".into()));
+        }
+
+        self.repos
+            .task_draft
+            .patch(draft_id, &account_ids, account_id, &input)
+            .await
+    }
+
+    /// 确认草稿，生成 source=ai_extract 的正式任务：
+    /// - 每个任务关联回来源资源（task_resource）
+    /// - 同一资源拆分出的任务以最早确认的任务为父任务，其余通过 ai_split 关联到它
+    ///
+    /// 标记草稿、生成任务和关联在同一事务内完成，任一步失败整批回滚；
+    /// 并发确认同一草稿时只有一方能标记成功，另一方返回 409
+    pub async fn accept(
+        &self,
+        account_id: Uuid,
+        draft_ids: &[Uuid],
+    ) -> Result<Vec<Task>, AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        let mut tx = self.repos.begin().await?;
+        let drafts = self
+            .claim(
+                &mut tx,
+                &account_ids,
+                account_id,
+                draft_ids,
+                DraftStatus::Accepted,
+            )
+            .await?;
+        let tasks = self.create_tasks(&mut tx, account_id, drafts).await?;
+        tx.commit().await?;
+        Ok(tasks)
+    }
+
+    pub async fn reject(&self, account_id: Uuid, draft_ids: &[Uuid]) -> Result<(), AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        let mut tx = self.repos.begin().await?;
+        self.claim(
+            &mut tx,
+            &account_ids,
+            account_id,
+            draft_ids,
+            DraftStatus::Rejected,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // 批量标记草稿状态，有草稿不存在或已处理时返回 409，由调用方回滚事务
+    async fn claim(
+        &self,
+        conn: &mut PgConnection,
+        account_ids: &[Uuid],
+        account_id: Uuid,
+        draft_ids: &[Uuid],
+        to: DraftStatus,
+    ) -> Result<Vec<TaskDraft>, AppError> {
+        let draft_ids: Vec<Uuid> = draft_ids
+            .iter()
+            .copied()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        if draft_ids.is_empty() {
+            return Err(AppError::BadRequest("draft_ids 不能为空".into()));
+        }
+        if draft_ids.len() > MAX_BATCH_SIZE {
+            return Err(AppError::BadRequest(format!(
+                "一次最多处理 {} 条草稿",
+                MAX_BATCH_SIZE
+            )));
+        }
+
+        let mut drafts = self
+            .repos
+            .task_draft
+            .claim(conn, &draft_ids, account_ids, to, account_id)
+            .await?;
+        if drafts.len() != draft_ids.len() {
+            return Err(AppError::Conflict("部分草稿不存在或已处理".into()));
+        }
+
+        drafts.sort_by_key(|d| (d.created_at, d.batch_id, d.position));
+        Ok(drafts)
+    }
+
+    async fn create_tasks(
+        &self,
+        conn: &mut PgConnection,
+        account_id: Uuid,
+        drafts: Vec<TaskDraft>,
+    ) -> Result<Vec<Task>, AppError> {
+        let now = chrono::Utc::now();
+        let claimed_ids: Vec<Uuid> = drafts.iter().map(|d| d.id).collect();
+        let mut tasks = Vec::with_capacity(drafts.len());
+        let mut by_resource: BTreeMap<Uuid, Vec<(Uuid, Uuid)>> = BTreeMap::new();
+
+        for draft in drafts {
+            let task = self
+                .repos
+                .task
+                .insert_in(conn, draft.to_task_create(account_id, now))
+                .await?;
+            self.repos
+                .task_draft
+                .set_task(conn, draft.id, task.id)
+                .await?;
+            self.repos
+                .task_resource
+                .insert_in(conn, task.id, draft.resource_id, account_id, &now)
+                .await?;
+
+            by_resource
+                .entry(draft.resource_id)
+                .or_default()
+                .push((task.id, task.account_id));
+            tasks.push(task);
+        }
+
+        for (resource_id, created) in by_resource {
+            let root = self
+                .repos
+                .task_draft
+                .find_split_root(conn, resource_id, &claimed_ids)
+                .await?;
+            let (root_id, children) = match root {
+                Some(root_id) => (root_id, &created[..]),
+                None => (created[0].0, &created[1..]),
+            };
+            for (task_id, owner_id) in children {
+                TaskLinkRepository::insert_in(
+                    conn,
+                    *owner_id,
+                    root_id,
+                    *task_id,
+                    LinkType::AiSplit,
+                    account_id,
+                    &now,
+                )
+                .await?;
+            }
+        }
+
+        Ok(tasks)
+    }
+
+    async fn visible_account_ids(&self, account_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        self.repos.account.visible_account_ids(account_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::resource::{FileType, Resource};
+    use crate::model::task::{Subject, TaskSource, TaskType};
+    use crate::test_support::{create_parent, create_resource};
+    use sqlx::PgPool;
+
+    // 写入一批待确认草稿，按 titles 顺序编号
+    async fn create_drafts(repos: &Repos, resource: &Resource, titles: &[&str]) -> Vec<Uuid> {
+        let now = chrono::Utc::now();
+        let batch_id = Uuid::new_v4();
+        let drafts: Vec<TaskDraft> = titles
+            .iter()
+            .enumerate()
+            .map(|(position, title)| TaskDraft {
+                id: Uuid::new_v4(),
+                account_id: resource.account_id,
+                resource_id: resource.id,
+                batch_id,
+                position: position as i32,
+                title: title.to_string(),
+                description: None,
+                task_type: TaskType::Homework,
+                subject: Subject::Math,
+                due_date: None,
+                status: DraftStatus::Pending,
+                task_id: None,
+                created_at: now,
+                created_by: resource.account_id,
+                updated_at: now,
+                updated_by: resource.account_id,
+            })
+            .collect();
+        repos
+            .task_draft
+            .insert_batch(resource.id, &drafts, resource.account_id, &now)
+            .await
+            .unwrap();
+        drafts.iter().map(|d| d.id).collect()
+    }
+
+    async fn draft(repos: &Repos, account_id: Uuid, draft_id: Uuid) -> TaskDraft {
+        repos
+            .task_draft
+            .find_by_id_and_account(draft_id, &[account_id])
+            .await
+            .unwrap()
+    }
+
+    async fn split_children(repos: &Repos, account_id: Uuid, task_id: Uuid) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = repos
+            .task_link
+            .find_children(task_id, &[account_id], Some(LinkType::AiSplit))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|l| l.task.id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[sqlx::test]
+    async fn accepted_drafts_become_linked_ai_tasks(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = TaskDraftService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let resource = create_resource(&repos, account_id, FileType::Image, "hash").await;
+        let drafts = create_drafts(&repos, &resource, &["第一题", "第二题", "第三题"]).await;
+
+        // 按批次内顺序生成，与请求顺序无关
+        let tasks = service
+            .accept(account_id, &[drafts[1], drafts[0]])
+            .await
+            .unwrap();
+        let titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, ["第一题", "第二题"]);
+        assert!(tasks.iter().all(|t| t.source == TaskSource::AiExtract));
+
+        for (draft_id, task) in drafts.iter().zip(&tasks) {
+            let draft = draft(&repos, account_id, *draft_id).await;
+            assert_eq!(draft.status, DraftStatus::Accepted);
+            assert_eq!(draft.task_id, Some(task.id));
+        }
+        let mut linked: Vec<Uuid> = repos
+            .task_resource
+            .find_tasks(resource.id, &[account_id])
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.task.id)
+            .collect();
+        linked.sort();
+        let mut expected: Vec<Uuid> = tasks.iter().map(|t| t.id).collect();
+        expected.sort();
+        assert_eq!(linked, expected);
+
+        // 以最早确认的任务为父任务，之后确认的任务也挂到它下面
+        let root = tasks[0].id;
+        assert_eq!(
+            split_children(&repos, account_id, root).await,
+            [tasks[1].id]
+        );
+        let later = service.accept(account_id, &[drafts[2]]).await.unwrap();
+        let mut expected = vec![tasks[1].id, later[0].id];
+        expected.sort();
+        assert_eq!(split_children(&repos, account_id, root).await, expected);
+    }
+
+    #[sqlx::test]
+    async fn handled_drafts_cannot_be_claimed_again(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = TaskDraftService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let resource = create_resource(&repos, account_id, FileType::Image, "hash").await;
+        let drafts = create_drafts(&repos, &resource, &["a", "b", "c"]).await;
+
+        service.accept(account_id, &[drafts[0]]).await.unwrap();
+        service.reject(account_id, &[drafts[1]]).await.unwrap();
+        for handled in [drafts[0], drafts[1]] {
+            assert!(matches!(
+                service.accept(account_id, &[handled]).await,
+                Err(AppError::Conflict(_))
+            ));
+            assert!(matches!(
+                service.reject(account_id, &[handled]).await,
+                Err(AppError::Conflict(_))
+            ));
+        }
+
+        // 批量中有一条已处理时整批不生效
+        assert!(matches!(
+            service.accept(account_id, &[drafts[2], drafts[0]]).await,
+            Err(AppError::Conflict(_))
+        ));
+        let pending = draft(&repos, account_id, drafts[2]).await;
+        assert_eq!(pending.status, DraftStatus::Pending);
+        assert_eq!(pending.task_id, None);
+    }
+
+    #[sqlx::test]
+    async fn failed_accept_rolls_back_everything(pool: PgPool) {
+        let repos = Repos::new(pool.clone());
+        let service = TaskDraftService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let resource = create_resource(&repos, account_id, FileType::Image, "hash").await;
+        let drafts = create_drafts(&repos, &resource, &["a", "b"]).await;
+
+        // 最后一步写 ai_split 关联时失败，此前生成的任务、资源关联和草稿状态都应回滚
+        sqlx::raw_sql(
+            r#"
+                CREATE FUNCTION fail_task_link() RETURNS trigger AS $$
+                BEGIN RAISE EXCEPTION 'task_link 写入失败'; END
+                $$ LANGUAGE plpgsql;
+                CREATE TRIGGER fail_task_link BEFORE INSERT ON task_link
+                    FOR EACH ROW EXECUTE FUNCTION fail_task_link();
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert!(service.accept(account_id, &drafts).await.is_err());
+        for draft_id in &drafts {
+            let draft = draft(&repos, account_id, *draft_id).await;
+            assert_eq!(draft.status, DraftStatus::Pending);
+            assert_eq!(draft.task_id, None);
+        }
+        let tasks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM task WHERE account_id = $1")
+            .bind(account_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(tasks, 0);
+        let links: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM task_resource")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(links, 0);
+    }
+
+    #[sqlx::test]
+    async fn drafts_of_deleted_resource_are_hidden(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = TaskDraftService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let resource = create_resource(&repos, account_id, FileType::Image, "hash").await;
+        let drafts = create_drafts(&repos, &resource, &["a"]).await;
+
+        repos
+            .resource
+            .delete(resource.id, &[account_id], account_id, |_| async {})
+            .await
+            .unwrap();
+
+        let query = TaskDraftListQuery {
+            resource_id: None,
+            status: None,
+        };
+        assert!(service.list(account_id, &query).await.unwrap().is_empty());
+        assert!(matches!(
+            service.accept(account_id, &drafts).await,
+            Err(AppError::Conflict(_))
+        ));
+    }
+}
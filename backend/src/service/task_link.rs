@@ -0,0 +1,355 @@
+use crate::app::context::Repos;
+use crate::error::AppError;
+use crate::model::task::{Task, TaskType};
+use crate::model::task_link::{
+    CreateTaskLinkRequest, LinkType, LinkedTask, LinkedTaskResponse, TaskTreeEdge, TaskTreeNode,
+};
+use crate::service::task::{auto_complete_plans, TaskAnnotations};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct TaskLinkService {
+    repos: Repos,
+}
+
+impl TaskLinkService {
+    pub fn new(repos: Repos) -> Self {
+        Self { repos }
+    }
+
+    /// 关联两个任务：当前任务为 parent，两端必须可见且属于同一账户
+    pub async fn attach(
+        &self,
+        parent_id: Uuid,
+        account_id: Uuid,
+        input: &CreateTaskLinkRequest,
+    ) -> Result<LinkedTaskResponse, AppError> {
+        if parent_id == input.child_task_id {
+            return Err(AppError::BadRequest("任务不能关联自身".into()));
+        }
+
+        let account_ids = self.visible_account_ids(account_id).await?;
+        let parent = self
+            .repos
+            .task
+            .find_by_id_and_account(parent_id, &account_ids)
+            .await?;
+        let child = self
+            .repos
+            .task
+            .find_by_id_and_account(input.child_task_id, &account_ids)
+            .await?;
+
+        if parent.account_id != child.account_id {
+            return Err(AppError::BadRequest("关联的任务必须属于同一账户".into()));
+        }
+        if input.link_type == LinkType::Includes && parent.task_type != TaskType::Plan {
+            return Err(AppError::BadRequest("只有计划可以包含任务".into()));
+        }
+
+        let now = chrono::Utc::now();
+        self.repos
+            .task_link
+            .insert(
+                parent.account_id,
+                parent.id,
+                child.id,
+                input.link_type,
+                account_id,
+                &now,
+            )
+            .await?;
+        if input.link_type == LinkType::Includes {
+            auto_complete_plans(&self.repos, vec![parent], account_id).await?;
+        }
+
+        let mut responses = self
+            .to_responses(vec![LinkedTask {
+                link_type: input.link_type,
+                linked_at: now,
+                task: child,
+            }])
+            .await?;
+        Ok(responses.remove(0))
+    }
+
+    /// 解除关联，link_type 为空时解除两任务间的全部关联
+    pub async fn detach(
+        &self,
+        parent_id: Uuid,
+        child_id: Uuid,
+        account_id: Uuid,
+        link_type: Option<LinkType>,
+    ) -> Result<(), AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        let parent = self
+            .repos
+            .task
+            .find_by_id_and_account(parent_id, &account_ids)
+            .await?;
+
+        self.repos
+            .task_link
+            .delete(parent_id, child_id, link_type)
+            .await?;
+
+        // 移出未完成的子任务后计划可能已全部完成
+        if link_type.is_none_or(|t| t == LinkType::Includes) {
+            auto_complete_plans(&self.repos, vec![parent], account_id).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn children(
+        &self,
+        task_id: Uuid,
+        account_id: Uuid,
+        link_type: Option<LinkType>,
+    ) -> Result<Vec<LinkedTaskResponse>, AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        self.repos
+            .task
+            .find_by_id_and_account(task_id, &account_ids)
+            .await?;
+
+        let children = self
+            .repos
+            .task_link
+            .find_children(task_id, &account_ids, link_type)
+            .await?;
+        self.to_responses(children).await
+    }
+
+    pub async fn parents(
+        &self,
+        task_id: Uuid,
+        account_id: Uuid,
+        link_type: Option<LinkType>,
+    ) -> Result<Vec<LinkedTaskResponse>, AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        self.repos
+            .task
+            .find_by_id_and_account(task_id, &account_ids)
+            .await?;
+
+        let parents = self
+            .repos
+            .task_link
+            .find_parents(task_id, &account_ids, link_type)
+            .await?;
+        self.to_responses(parents).await
+    }
+
+    /// 展开任务树（includes / ai_split），根任务需已通过可见性校验
+    pub async fn tree(&self, root: &Task, account_id: Uuid) -> Result<Vec<TaskTreeNode>, AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        let edges = self
+            .repos
+            .task_link
+            .find_tree(root.id, &account_ids)
+            .await?;
+
+        let annotations = TaskAnnotations::load(&self.repos, edges.iter().map(|e| &e.task)).await?;
+
+        // 同一任务被多个计划包含时，它下面的边沿每条路径各查出一次，这里去重
+        let mut seen = HashSet::new();
+        let mut by_parent: HashMap<Uuid, Vec<TaskTreeEdge>> = HashMap::new();
+        for edge in edges {
+            if seen.insert((edge.parent_task_id, edge.task.id, edge.link_type.as_str())) {
+                by_parent.entry(edge.parent_task_id).or_default().push(edge);
+            }
+        }
+
+        Ok(build_tree(
+            root.id,
+            &by_parent,
+            &annotations,
+            &mut vec![root.id],
+        ))
+    }
+
+    async fn to_responses(
+        &self,
+        linked: Vec<LinkedTask>,
+    ) -> Result<Vec<LinkedTaskResponse>, AppError> {
+        let annotations =
+            TaskAnnotations::load(&self.repos, linked.iter().map(|l| &l.task)).await?;
+
+        Ok(linked
+            .into_iter()
+            .map(|l| LinkedTaskResponse {
+                link_type: l.link_type,
+                linked_at: l.linked_at,
+                task: annotations.response(l.task),
+            })
+            .collect())
+    }
+
+    async fn visible_account_ids(&self, account_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        self.repos.account.visible_account_ids(account_id).await
+    }
+}
+
+// 按 parent 分组的边组装成嵌套结构；path 记录当前路径，遇到环直接截断。
+// 同一任务出现在多处时（被多个计划包含）每处都完整展开，深度已由查询限制
+fn build_tree(
+    parent_id: Uuid,
+    by_parent: &HashMap<Uuid, Vec<TaskTreeEdge>>,
+    annotations: &TaskAnnotations,
+    path: &mut Vec<Uuid>,
+) -> Vec<TaskTreeNode> {
+    let Some(edges) = by_parent.get(&parent_id) else {
+        return Vec::new();
+    };
+
+    let mut nodes = Vec::with_capacity(edges.len());
+    for edge in edges {
+        let task_id = edge.task.id;
+        if path.contains(&task_id) {
+            continue;
+        }
+        path.push(task_id);
+        let children = build_tree(task_id, by_parent, annotations, path);
+        path.pop();
+
+        let mut task = annotations.response(edge.task.clone());
+        task.children = Some(children);
+        nodes.push(TaskTreeNode {
+            link_type: edge.link_type,
+            task,
+        });
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::task::TaskTransition;
+    use crate::service::task::TaskService;
+    use crate::test_support::{create_parent, create_plan, create_task};
+    use sqlx::PgPool;
+
+    async fn link(
+        service: &TaskLinkService,
+        parent: &Task,
+        child: &Task,
+        link_type: LinkType,
+    ) -> Result<LinkedTaskResponse, AppError> {
+        let input = CreateTaskLinkRequest {
+            child_task_id: child.id,
+            link_type,
+        };
+        service.attach(parent.id, parent.account_id, &input).await
+    }
+
+    fn is_cycle(result: Result<LinkedTaskResponse, AppError>) -> bool {
+        matches!(result, Err(AppError::BadRequest(message)) if message.contains("循环"))
+    }
+
+    #[sqlx::test]
+    async fn dependency_cycles_are_rejected(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = TaskLinkService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let a = create_task(&repos, account_id, "a").await;
+        let b = create_task(&repos, account_id, "b").await;
+        let c = create_task(&repos, account_id, "c").await;
+
+        link(&service, &a, &b, LinkType::DependsOn).await.unwrap();
+        link(&service, &b, &c, LinkType::DependsOn).await.unwrap();
+        assert!(is_cycle(link(&service, &c, &a, LinkType::DependsOn).await));
+        assert!(is_cycle(link(&service, &b, &a, LinkType::DependsOn).await));
+        assert!(matches!(
+            link(&service, &a, &a, LinkType::DependsOn).await,
+            Err(AppError::BadRequest(_))
+        ));
+        // 重复关联返回 409
+        assert!(matches!(
+            link(&service, &a, &b, LinkType::DependsOn).await,
+            Err(AppError::Conflict(_))
+        ));
+
+        // 依赖图和层级图分别检测，反向的拆分关系不构成依赖环
+        link(&service, &c, &a, LinkType::AiSplit).await.unwrap();
+    }
+
+    #[sqlx::test]
+    async fn hierarchy_cycles_span_includes_and_ai_split(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = TaskLinkService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let plan = create_plan(&repos, account_id, false).await;
+        let sub_plan = create_plan(&repos, account_id, false).await;
+        let task = create_task(&repos, account_id, "task").await;
+
+        link(&service, &plan, &sub_plan, LinkType::Includes)
+            .await
+            .unwrap();
+        link(&service, &sub_plan, &task, LinkType::AiSplit)
+            .await
+            .unwrap();
+        assert!(is_cycle(
+            link(&service, &task, &plan, LinkType::AiSplit).await
+        ));
+        assert!(is_cycle(
+            link(&service, &sub_plan, &plan, LinkType::Includes).await
+        ));
+
+        // 同一任务可以挂在多个计划下（菱形），不是环
+        link(&service, &plan, &task, LinkType::Includes)
+            .await
+            .unwrap();
+    }
+
+    #[sqlx::test]
+    async fn open_prerequisite_blocks_completion(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = TaskLinkService::new(repos.clone());
+        let tasks = TaskService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let essay = create_task(&repos, account_id, "作文").await;
+        let outline = create_task(&repos, account_id, "提纲").await;
+        link(&service, &essay, &outline, LinkType::DependsOn)
+            .await
+            .unwrap();
+
+        assert!(tasks.to_response(essay.clone()).await.unwrap().blocked);
+        assert!(matches!(
+            tasks
+                .transition(essay.id, account_id, TaskTransition::Complete, false)
+                .await,
+            Err(AppError::Conflict(_))
+        ));
+
+        tasks
+            .transition(outline.id, account_id, TaskTransition::Complete, false)
+            .await
+            .unwrap();
+        assert!(!tasks.to_response(essay.clone()).await.unwrap().blocked);
+        tasks
+            .transition(essay.id, account_id, TaskTransition::Complete, false)
+            .await
+            .unwrap();
+    }
+
+    #[sqlx::test]
+    async fn force_completes_blocked_task(pool: PgPool) {
+        let repos = Repos::new(pool);
+        let service = TaskLinkService::new(repos.clone());
+        let tasks = TaskService::new(repos.clone());
+        let account_id = create_parent(&repos).await;
+        let essay = create_task(&repos, account_id, "作文").await;
+        let outline = create_task(&repos, account_id, "提纲").await;
+        link(&service, &essay, &outline, LinkType::DependsOn)
+            .await
+            .unwrap();
+
+        let done = tasks
+            .transition(essay.id, account_id, TaskTransition::Complete, true)
+            .await
+            .unwrap();
+        assert!(done.completed_at.is_some());
+    }
+}
@@ -0,0 +1,135 @@
+use crate::app::context::Repos;
+use crate::error::AppError;
+use crate::model::resource::ResourceResponse;
+use crate::model::task::Task;
+use crate::model::task_resource::{LinkedResourceResponse, ResourceTaskResponse};
+use crate::service::task::TaskAnnotations;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct TaskResourceService {
+    repos: Repos,
+}
+
+impl TaskResourceService {
+    pub fn new(repos: Repos) -> Self {
+        Self { repos }
+    }
+
+    /// 给任务关联资源：任务和资源都必须可见且属于同一账户
+    pub async fn attach(
+        &self,
+        task_id: Uuid,
+        resource_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<LinkedResourceResponse, AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        let task = self
+            .repos
+            .task
+            .find_by_id_and_account(task_id, &account_ids)
+            .await?;
+        let resource = self
+            .repos
+            .resource
+            .find_by_id_and_account(resource_id, &account_ids)
+            .await?;
+
+        if task.account_id != resource.account_id {
+            return Err(AppError::BadRequest("任务和资源必须属于同一账户".into()));
+        }
+
+        let now = chrono::Utc::now();
+        self.repos
+            .task_resource
+            .insert(task.id, resource.id, account_id, &now)
+            .await?;
+
+        Ok(LinkedResourceResponse {
+            linked_at: now,
+            resource: resource.into(),
+        })
+    }
+
+    pub async fn detach(
+        &self,
+        task_id: Uuid,
+        resource_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<(), AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        self.repos
+            .task
+            .find_by_id_and_account(task_id, &account_ids)
+            .await?;
+
+        self.repos.task_resource.delete(task_id, resource_id).await
+    }
+
+    /// 任务关联的资源
+    pub async fn resources(
+        &self,
+        task_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Vec<LinkedResourceResponse>, AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        self.repos
+            .task
+            .find_by_id_and_account(task_id, &account_ids)
+            .await?;
+
+        let resources = self
+            .repos
+            .task_resource
+            .find_resources(task_id, &account_ids)
+            .await?;
+        Ok(resources.into_iter().map(Into::into).collect())
+    }
+
+    /// 使用该资源的任务
+    pub async fn tasks(
+        &self,
+        resource_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Vec<ResourceTaskResponse>, AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        self.repos
+            .resource
+            .find_by_id_and_account(resource_id, &account_ids)
+            .await?;
+
+        let tasks = self
+            .repos
+            .task_resource
+            .find_tasks(resource_id, &account_ids)
+            .await?;
+        let annotations = TaskAnnotations::load(&self.repos, tasks.iter().map(|t| &t.task)).await?;
+
+        Ok(tasks
+            .into_iter()
+            .map(|t| ResourceTaskResponse {
+                linked_at: t.linked_at,
+                task: annotations.response(t.task),
+            })
+            .collect())
+    }
+
+    /// 任务详情中嵌入的资源摘要，任务需已通过可见性校验
+    pub async fn summaries(
+        &self,
+        task: &Task,
+        account_id: Uuid,
+    ) -> Result<Vec<ResourceResponse>, AppError> {
+        let account_ids = self.visible_account_ids(account_id).await?;
+        let resources = self
+            .repos
+            .task_resource
+            .find_resources(task.id, &account_ids)
+            .await?;
+        Ok(resources.into_iter().map(|l| l.resource.into()).collect())
+    }
+
+    async fn visible_account_ids(&self, account_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        self.repos.account.visible_account_ids(account_id).await
+    }
+}
@@ -0,0 +1,89 @@
+use crate::error::AppError;
+use crate::storage::Storage;
+use salvo::async_trait;
+use std::io::SeekFrom;
+use std::path::{Component, Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+/// 本地文件系统存储，开发环境 / 单机部署使用
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub async fn new(root: &str) -> Result<Self, AppError> {
+        tokio::fs::create_dir_all(root).await.map_err(|e| {
+            tracing::error!(err = ?e, root, "创建存储目录失败");
+            AppError::Internal
+        })?;
+        Ok(Self { root: root.into() })
+    }
+
+    // 只接受普通相对路径，防止 key 跳出存储目录
+    fn path_of(&self, key: &str) -> Result<PathBuf, AppError> {
+        let relative = Path::new(key);
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            tracing::error!(key, "非法的存储 key");
+            return Err(AppError::Internal);
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, data: &[u8], _content_type: &str) -> Result<String, AppError> {
+        let path = self.path_of(key)?;
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await.map_err(io_error)?;
+        }
+        tokio::fs::write(&path, data).await.map_err(io_error)?;
+
+        Ok(format!("local://{}", key))
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, AppError> {
+        let path = self.path_of(key)?;
+        tokio::fs::read(&path).await.map_err(io_error)
+    }
+
+    async fn get_range(&self, key: &str, start: u64, end: u64) -> Result<Vec<u8>, AppError> {
+        let path = self.path_of(key)?;
+        let mut file = tokio::fs::File::open(&path).await.map_err(io_error)?;
+        file.seek(SeekFrom::Start(start)).await.map_err(io_error)?;
+
+        let mut data = Vec::with_capacity((end - start + 1) as usize);
+        file.take(end - start + 1)
+            .read_to_end(&mut data)
+            .await
+            .map_err(io_error)?;
+        Ok(data)
+    }
+
+    async fn size(&self, key: &str) -> Result<u64, AppError> {
+        let path = self.path_of(key)?;
+        let metadata = tokio::fs::metadata(&path).await.map_err(io_error)?;
+        Ok(metadata.len())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        let path = self.path_of(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn io_error(err: std::io::Error) -> AppError {
+    match err.kind() {
+        std::io::ErrorKind::NotFound => AppError::NotFound,
+        _ => {
+            tracing::error!(err = ?err, "本地存储读写失败");
+            AppError::Internal
+        }
+    }
+}
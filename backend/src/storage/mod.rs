@@ -0,0 +1,44 @@
+use crate::config::{AppConfig, StorageBackend};
+use crate::error::AppError;
+use salvo::async_trait;
+use std::sync::Arc;
+
+pub mod local;
+pub mod s3;
+pub mod signer;
+pub mod sniff;
+pub mod thumbnail;
+
+/// 文件存储后端，key 为存储内的相对路径（如 resources/{account_id}/{resource_id}）
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// 写入对象，返回可记录到 storage_url 的地址
+    async fn put(&self, key: &str, data: &[u8], content_type: &str) -> Result<String, AppError>;
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, AppError>;
+
+    /// 读取 [start, end] 字节区间（闭区间），用于 HTTP Range 请求
+    async fn get_range(&self, key: &str, start: u64, end: u64) -> Result<Vec<u8>, AppError>;
+
+    /// 对象大小（字节）
+    async fn size(&self, key: &str) -> Result<u64, AppError>;
+
+    /// 删除对象，不存在时不报错
+    async fn delete(&self, key: &str) -> Result<(), AppError>;
+}
+
+/// 按配置创建存储后端
+pub async fn create_storage(config: &AppConfig) -> Result<Arc<dyn Storage>, AppError> {
+    match config.storage_backend {
+        StorageBackend::Local => Ok(Arc::new(
+            local::LocalStorage::new(&config.storage_local_root).await?,
+        )),
+        StorageBackend::S3 => {
+            let oss = config
+                .oss
+                .as_ref()
+                .ok_or_else(|| AppError::EnvVar("OSS_BUCKET".into()))?;
+            Ok(Arc::new(s3::S3Storage::new(oss)?))
+        }
+    }
+}
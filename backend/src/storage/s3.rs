@@ -0,0 +1,98 @@
+use crate::config::OssConfig;
+use crate::error::AppError;
+use crate::storage::Storage;
+use s3::creds::Credentials;
+use s3::error::S3Error;
+use s3::{Bucket, Region};
+use salvo::async_trait;
+
+/// S3 兼容对象存储：阿里云 OSS、MinIO 等
+pub struct S3Storage {
+    bucket: Box<Bucket>,
+}
+
+impl S3Storage {
+    pub fn new(config: &OssConfig) -> Result<Self, AppError> {
+        // OSS_ENDPOINT 可以不带协议（如 oss-cn-hangzhou.aliyuncs.com），默认 https
+        let endpoint = if config.endpoint.contains("://") {
+            config.endpoint.clone()
+        } else {
+            format!("https://{}", config.endpoint)
+        };
+        let region = Region::Custom {
+            region: config.region.clone(),
+            endpoint,
+        };
+        let credentials = Credentials::new(
+            Some(&config.access_key_id),
+            Some(&config.access_key_secret),
+            None,
+            None,
+            None,
+        )
+        .map_err(|e| AppError::ConfigParse(format!("OSS 凭证无效: {}", e)))?;
+
+        let bucket = Bucket::new(&config.bucket, region, credentials)
+            .map_err(|e| AppError::ConfigParse(format!("OSS 配置无效: {}", e)))?;
+        let bucket = if config.path_style {
+            bucket.with_path_style()
+        } else {
+            bucket
+        };
+
+        Ok(Self { bucket })
+    }
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, data: &[u8], content_type: &str) -> Result<String, AppError> {
+        self.bucket
+            .put_object_with_content_type(key, data, content_type)
+            .await
+            .map_err(s3_error)?;
+
+        Ok(format!("s3://{}/{}", self.bucket.name(), key))
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, AppError> {
+        let response = self.bucket.get_object(key).await.map_err(s3_error)?;
+        Ok(response.bytes().to_vec())
+    }
+
+    async fn get_range(&self, key: &str, start: u64, end: u64) -> Result<Vec<u8>, AppError> {
+        let response = self
+            .bucket
+            .get_object_range(key, start, Some(end))
+            .await
+            .map_err(s3_error)?;
+        Ok(response.bytes().to_vec())
+    }
+
+    async fn size(&self, key: &str) -> Result<u64, AppError> {
+        let (head, _) = self.bucket.head_object(key).await.map_err(s3_error)?;
+        head.content_length
+            .and_then(|len| u64::try_from(len).ok())
+            .ok_or_else(|| {
+                tracing::error!(key, "对象存储未返回 Content-Length");
+                AppError::Internal
+            })
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        match self.bucket.delete_object(key).await {
+            Err(S3Error::HttpFailWithBody(404, _)) => Ok(()),
+            other => other.map(|_| ()).map_err(s3_error),
+        }
+    }
+}
+
+fn s3_error(err: S3Error) -> AppError {
+    match err {
+        S3Error::HttpFailWithBody(404, _) => AppError::NotFound,
+        e => {
+            tracing::error!(err = ?e, "对象存储请求失败");
+            AppError::Internal
+        }
+    }
+}
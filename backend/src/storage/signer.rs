@@ -0,0 +1,111 @@
+use crate::config::AppConfig;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 签名后的下载链接
+#[derive(Debug, Serialize)]
+pub struct SignedUrl {
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 下载链接签名：HMAC-SHA256(path + 过期时间)，链接形如 {path}?expires=...&sig=...
+pub struct UrlSigner<'a> {
+    key: &'a [u8],
+    ttl: Duration,
+}
+
+impl<'a> UrlSigner<'a> {
+    pub fn from_config(config: &'a AppConfig) -> Self {
+        Self {
+            key: config.download_signing_key.as_bytes(),
+            ttl: Duration::seconds(config.download_url_ttl_secs),
+        }
+    }
+
+    pub fn sign(&self, path: &str) -> SignedUrl {
+        let expires_at = Utc::now() + self.ttl;
+        let expires = expires_at.timestamp();
+        let sig = hex::encode(self.mac(path, expires).finalize().into_bytes());
+
+        SignedUrl {
+            url: format!("{}?expires={}&sig={}", path, expires, sig),
+            expires_at,
+        }
+    }
+
+    /// 校验签名和有效期，签名比较为常量时间
+    pub fn verify(&self, path: &str, expires: i64, sig: &str) -> bool {
+        if expires < Utc::now().timestamp() {
+            return false;
+        }
+        let Ok(sig) = hex::decode(sig) else {
+            return false;
+        };
+        self.mac(path, expires).verify_slice(&sig).is_ok()
+    }
+
+    fn mac(&self, path: &str, expires: i64) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(self.key).expect("HMAC 支持任意长度的密钥");
+        mac.update(path.as_bytes());
+        mac.update(b":");
+        mac.update(expires.to_string().as_bytes());
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATH: &str = "/api/files/abc";
+
+    fn signer(ttl_secs: i64) -> UrlSigner<'static> {
+        UrlSigner {
+            key: b"test-signing-key",
+            ttl: Duration::seconds(ttl_secs),
+        }
+    }
+
+    // 从签名链接中取出 expires 和 sig
+    fn query(signed: &SignedUrl) -> (i64, String) {
+        let (_, query) = signed.url.split_once('?').expect("链接带查询参数");
+        let mut expires = 0;
+        let mut sig = String::new();
+        for pair in query.split('&') {
+            match pair.split_once('=') {
+                Some(("expires", v)) => expires = v.parse().expect("expires 为时间戳"),
+                Some(("sig", v)) => sig = v.to_string(),
+                _ => {}
+            }
+        }
+        (expires, sig)
+    }
+
+    #[test]
+    fn signed_url_verifies() {
+        let signer = signer(600);
+        let (expires, sig) = query(&signer.sign(PATH));
+        assert!(signer.verify(PATH, expires, &sig));
+    }
+
+    #[test]
+    fn expired_url_is_rejected() {
+        let signer = signer(-60);
+        let (expires, sig) = query(&signer.sign(PATH));
+        assert!(!signer.verify(PATH, expires, &sig));
+    }
+
+    #[test]
+    fn signature_is_bound_to_path_and_expiry() {
+        let signer = signer(600);
+        let (expires, sig) = query(&signer.sign(PATH));
+        assert!(!signer.verify("/api/files/other", expires, &sig));
+        assert!(!signer.verify(PATH, expires + 3600, &sig));
+        assert!(!signer.verify(PATH, expires, "not-hex"));
+    }
+}
@@ -0,0 +1,138 @@
+use crate::error::AppError;
+use crate::model::resource::FileType;
+
+// 无论内容如何都拒绝的扩展名（可执行文件 / 脚本 / 安装包）
+const BLOCKED_EXTENSIONS: &[&str] = &[
+    "exe", "dll", "com", "scr", "msi", "bat", "cmd", "ps1", "vbs", "sh", "jar", "apk", "app", "dmg",
+];
+
+/// 按文件头识别出的真实类型
+#[derive(Debug)]
+pub struct Sniffed {
+    pub mime: String,
+    pub file_type: FileType,
+}
+
+/// 按魔数识别文件类型，不信任客户端声明的 Content-Type
+///
+/// - 可执行文件（PE / ELF / Mach-O 等）、带 shebang 的脚本、危险扩展名一律拒绝
+/// - 扩展名对应的类型与实际内容不一致时拒绝（如改名为 .pdf 的图片）
+/// - 无魔数的内容，合法 UTF-8 且不含 NUL 视为纯文本
+pub fn sniff(file_name: &str, data: &[u8]) -> Result<Sniffed, AppError> {
+    let extension = file_name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    if BLOCKED_EXTENSIONS.contains(&extension.as_str())
+        || infer::is_app(data)
+        || data.starts_with(b"#!")
+    {
+        return Err(AppError::UnsupportedMediaType(
+            "不允许上传可执行文件".into(),
+        ));
+    }
+
+    let mut mime = match infer::get(data) {
+        Some(kind) => kind.mime_type().to_string(),
+        None if is_text(data) => "text/plain".to_string(),
+        None => "application/octet-stream".to_string(),
+    };
+
+    let claimed = mime_guess::from_path(file_name).first();
+    let claimed_type = claimed
+        .as_ref()
+        .map(|m| FileType::from_mime(m.essence_str()))
+        .unwrap_or(FileType::Other);
+    let mut file_type = FileType::from_mime(&mime);
+
+    // Office 文档本质是 zip，识别不出具体格式时以扩展名为准
+    if mime == "application/zip" && claimed_type == FileType::Doc {
+        if let Some(claimed) = &claimed {
+            file_type = FileType::Doc;
+            mime = claimed.essence_str().to_string();
+        }
+    }
+    // 扩展名无法归类（如 .csv）时不做一致性校验，按内容归类
+    if claimed_type != FileType::Other && claimed_type != file_type {
+        return Err(AppError::UnsupportedMediaType(format!(
+            "文件内容（{}）与扩展名 .{} 不符",
+            mime, extension
+        )));
+    }
+
+    Ok(Sniffed { mime, file_type })
+}
+
+fn is_text(data: &[u8]) -> bool {
+    !data.contains(&0) && std::str::from_utf8(data).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    const PDF: &[u8] = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n";
+    // 只有 zip 本地文件头，识别不出具体的 Office 格式
+    const ZIP: &[u8] = b"PK\x03\x04\x14\0\0\0\x08\0";
+
+    fn rejected(file_name: &str, data: &[u8]) -> bool {
+        matches!(
+            sniff(file_name, data),
+            Err(AppError::UnsupportedMediaType(_))
+        )
+    }
+
+    #[test]
+    fn executables_are_rejected() {
+        // PE / ELF 按内容识别，与扩展名无关
+        assert!(rejected("作业.pdf", b"MZ\x90\0\x03\0\0\0\x04\0"));
+        assert!(rejected("notes.txt", b"\x7fELF\x02\x01\x01\0"));
+        // 脚本和危险扩展名
+        assert!(rejected("run.txt", b"#!/bin/sh\nrm -rf /\n"));
+        assert!(rejected("setup.EXE", b"hello"));
+        assert!(rejected("install.sh", b"echo hi\n"));
+    }
+
+    #[test]
+    fn extension_must_match_content() {
+        assert!(rejected("作业.pdf", PNG));
+        assert!(rejected("scan.png", PDF));
+        assert!(rejected("report.docx", PDF));
+
+        let png = sniff("scan.PNG", PNG).unwrap();
+        assert_eq!(png.mime, "image/png");
+        assert_eq!(png.file_type, FileType::Image);
+        let pdf = sniff("作业.pdf", PDF).unwrap();
+        assert_eq!(pdf.mime, "application/pdf");
+        assert_eq!(pdf.file_type, FileType::Pdf);
+    }
+
+    #[test]
+    fn unclassified_extension_uses_content_type() {
+        // .csv 不做扩展名校验，类型取自内容而不是文件名
+        let csv = sniff("成绩.csv", "姓名,分数\n小明,98\n".as_bytes()).unwrap();
+        assert_eq!(csv.mime, "text/plain");
+        assert_eq!(csv.file_type, FileType::Doc);
+
+        let unknown = sniff("data.bin", b"\0\x01\x02\x03").unwrap();
+        assert_eq!(unknown.mime, "application/octet-stream");
+        assert_eq!(unknown.file_type, FileType::Other);
+    }
+
+    #[test]
+    fn zip_named_as_office_document_is_doc() {
+        let docx = sniff("作业.docx", ZIP).unwrap();
+        assert_eq!(
+            docx.mime,
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        );
+        assert_eq!(docx.file_type, FileType::Doc);
+
+        // 其它扩展名的 zip 仍按内容处理
+        let zip = sniff("资料.zip", ZIP).unwrap();
+        assert_eq!(zip.mime, "application/zip");
+        assert_eq!(zip.file_type, FileType::Other);
+        assert!(rejected("作业.pdf", ZIP));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::error::AppError;
+use crate::model::resource::FileType;
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use std::io::Cursor;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+// 缩略图长边像素
+const THUMBNAIL_SIZE: u32 = 320;
+const JPEG_QUALITY: u8 = 80;
+// 外部渲染器超时，避免异常 PDF 卡住后台任务
+const RENDER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 生成 JPEG 缩略图；类型不支持或未配置 PDF 渲染器时返回 None，配置的渲染器不存在时返回错误
+pub async fn render(
+    file_type: FileType,
+    data: Vec<u8>,
+    pdf_renderer: Option<&str>,
+) -> Result<Option<Vec<u8>>, AppError> {
+    match (file_type, pdf_renderer) {
+        (FileType::Image, _) => {
+            let thumbnail = tokio::task::spawn_blocking(move || resize_image(&data))
+                .await
+                .map_err(|e| {
+                    tracing::error!(err = ?e, "缩略图任务异常退出");
+                    AppError::Internal
+                })??;
+            Ok(Some(thumbnail))
+        }
+        (FileType::Pdf, Some(renderer)) => render_pdf(renderer, data).await,
+        _ => Ok(None),
+    }
+}
+
+// 按 EXIF 方向摆正（手机照片常见），等比缩放后编码为 JPEG
+fn resize_image(data: &[u8]) -> Result<Vec<u8>, AppError> {
+    let decode_err = |e: image::ImageError| {
+        tracing::warn!(err = ?e, "图片解码失败");
+        AppError::BadRequest("无法解析图片".into())
+    };
+
+    let mut decoder = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| decode_err(e.into()))?
+        .into_decoder()
+        .map_err(decode_err)?;
+    let orientation = decoder.orientation().map_err(decode_err)?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(decode_err)?;
+    image.apply_orientation(orientation);
+
+    let thumbnail = image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgb8();
+    let mut buf = Vec::new();
+    JpegEncoder::new_with_quality(&mut buf, JPEG_QUALITY)
+        .encode_image(&thumbnail)
+        .map_err(|e| {
+            tracing::error!(err = ?e, "缩略图编码失败");
+            AppError::Internal
+        })?;
+    Ok(buf)
+}
+
+// 调用 pdftoppm 渲染首页：PDF 从 stdin 读入，JPEG 输出到 stdout
+async fn render_pdf(renderer: &str, data: Vec<u8>) -> Result<Option<Vec<u8>>, AppError> {
+    let size = THUMBNAIL_SIZE.to_string();
+    let spawned = Command::new(renderer)
+        .args(["-f", "1", "-l", "1", "-singlefile", "-jpeg"])
+        .args(["-scale-to", &size, "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+    // 渲染器不存在属于配置错误，任务失败后按重试策略进入死信，留空 PDF_RENDERER 可关闭
+    let mut child = spawned.map_err(|e| {
+        tracing::error!(err = ?e, renderer, "启动 PDF 渲染器失败");
+        AppError::Internal
+    })?;
+
+    // 边写 stdin 边读 stdout，避免管道缓冲区写满互相等待
+    let mut stdin = child.stdin.take().ok_or(AppError::Internal)?;
+    let writer = tokio::spawn(async move {
+        // 渲染器可能只读取部分内容就退出，写入失败不影响结果
+        let _ = stdin.write_all(&data).await;
+    });
+
+    let output = tokio::time::timeout(RENDER_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| {
+            tracing::warn!(renderer, "PDF 渲染超时");
+            AppError::Internal
+        })?
+        .map_err(|e| {
+            tracing::error!(err = ?e, "等待 PDF 渲染器失败");
+            AppError::Internal
+        })?;
+    writer.abort();
+
+    if !output.status.success() || output.stdout.is_empty() {
+        tracing::warn!(
+            status = ?output.status,
+            stderr = %String::from_utf8_lossy(&output.stderr),
+            "PDF 渲染失败"
+        );
+        return Err(AppError::Internal);
+    }
+    Ok(Some(output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, Rgb, RgbImage};
+
+    // 左半红、右半蓝的测试图
+    fn two_tone(width: u32, height: u32) -> RgbImage {
+        RgbImage::from_fn(width, height, |x, _| {
+            if x < width / 2 {
+                Rgb([255, 0, 0])
+            } else {
+                Rgb([0, 0, 255])
+            }
+        })
+    }
+
+    fn encode(image: &RgbImage, format: image::ImageFormat) -> Vec<u8> {
+        let mut buf = Cursor::new(Vec::new());
+        image.write_to(&mut buf, format).unwrap();
+        buf.into_inner()
+    }
+
+    // 在 SOI 之后插入只含 Orientation 标签的 EXIF 段
+    fn with_orientation(jpeg: &[u8], orientation: u8) -> Vec<u8> {
+        let mut exif = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01".to_vec();
+        exif.extend_from_slice(&[0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, orientation, 0, 0]);
+        exif.extend_from_slice(&[0, 0, 0, 0]);
+        let mut out = jpeg[..2].to_vec();
+        out.extend_from_slice(&[0xff, 0xe1]);
+        out.extend_from_slice(&(exif.len() as u16 + 2).to_be_bytes());
+        out.extend_from_slice(&exif);
+        out.extend_from_slice(&jpeg[2..]);
+        out
+    }
+
+    fn decode(data: &[u8]) -> DynamicImage {
+        image::load_from_memory_with_format(data, image::ImageFormat::Jpeg).unwrap()
+    }
+
+    #[test]
+    fn thumbnail_fits_bound_and_keeps_aspect_ratio() {
+        for (width, height, expected) in [
+            (1000, 500, (320, 160)),
+            (600, 1800, (107, 320)),
+            (640, 640, (320, 320)),
+        ] {
+            let data = encode(&two_tone(width, height), image::ImageFormat::Png);
+            let thumbnail = decode(&resize_image(&data).unwrap());
+            assert_eq!(thumbnail.dimensions(), expected, "{}x{}", width, height);
+        }
+    }
+
+    #[test]
+    fn exif_orientation_is_applied() {
+        let jpeg = encode(&two_tone(400, 200), image::ImageFormat::Jpeg);
+        // 6：顺时针旋转 90° 后显示，原图左侧（红）转到上方
+        let thumbnail = decode(&resize_image(&with_orientation(&jpeg, 6)).unwrap()).to_rgb8();
+        assert_eq!(thumbnail.dimensions(), (160, 320));
+        let top = thumbnail.get_pixel(80, 40);
+        let bottom = thumbnail.get_pixel(80, 280);
+        assert!(top[0] > 200 && top[2] < 60, "{:?}", top);
+        assert!(bottom[2] > 200 && bottom[0] < 60, "{:?}", bottom);
+    }
+
+    #[test]
+    fn undecodable_image_is_bad_request() {
+        assert!(matches!(
+            resize_image(b"not an image"),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn missing_pdf_renderer_is_an_error() {
+        let result = render(
+            FileType::Pdf,
+            b"%PDF-1.4".to_vec(),
+            Some("/nonexistent/pdftoppm"),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Internal)));
+    }
+
+    #[tokio::test]
+    async fn failing_pdf_renderer_is_an_error() {
+        let result = render(FileType::Pdf, b"%PDF-1.4".to_vec(), Some("false")).await;
+        assert!(matches!(result, Err(AppError::Internal)));
+    }
+
+    #[tokio::test]
+    async fn pdf_without_renderer_is_skipped() {
+        let result = render(FileType::Pdf, b"%PDF-1.4".to_vec(), None).await;
+        assert!(matches!(result, Ok(None)));
+    }
+}
@@ -0,0 +1,132 @@
+//! 测试公用的数据准备。数据库由 #[sqlx::test] 为每个测试单独创建并执行 migrations，
+//! 需设置 DATABASE_URL 指向可建库的 PostgreSQL
+use crate::app::context::Repos;
+use crate::config::AppConfig;
+use crate::model::account::{AccountCreate, Role};
+use crate::model::resource::{FileType, Resource, ResourceCreate};
+use crate::model::task::{Subject, Task, TaskCreate, TaskSource, TaskType};
+use crate::storage::local::LocalStorage;
+use crate::storage::Storage;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 测试配置：只设置必填项，其余取默认值
+pub fn config() -> AppConfig {
+    let vars = HashMap::from([
+        (
+            "DATABASE_URL".to_string(),
+            "postgres://localhost/learnnest".to_string(),
+        ),
+        (
+            "JWT_SECRET".to_string(),
+            "test-jwt-secret-0123456789abcdef".to_string(),
+        ),
+    ]);
+    AppConfig::from_vars(vars).expect("测试配置").0
+}
+
+/// 创建一个家长账户（自成一个家庭），返回账户 ID
+pub async fn create_parent(repos: &Repos) -> Uuid {
+    let id = Uuid::new_v4();
+    let now = Utc::now();
+    repos
+        .account
+        .insert(AccountCreate {
+            id,
+            username: Some(format!("parent-{}", id)),
+            nickname: "家长".into(),
+            password_hash: None,
+            role: Role::Parent.as_str().to_string(),
+            created_at: now,
+            updated_at: now,
+            created_by: id,
+            updated_by: id,
+            is_deleted: false,
+            family_id: id,
+        })
+        .await
+        .expect("创建账户");
+    id
+}
+
+/// 创建手动录入的数学作业，无截止时间
+pub async fn create_task(repos: &Repos, account_id: Uuid, title: &str) -> Task {
+    insert_task(repos, account_id, title, TaskType::Homework, false).await
+}
+
+/// 创建计划，auto_complete 为 true 时子任务全部完成后自动完成
+pub async fn create_plan(repos: &Repos, account_id: Uuid, auto_complete: bool) -> Task {
+    insert_task(repos, account_id, "计划", TaskType::Plan, auto_complete).await
+}
+
+async fn insert_task(
+    repos: &Repos,
+    account_id: Uuid,
+    title: &str,
+    task_type: TaskType,
+    auto_complete: bool,
+) -> Task {
+    repos
+        .task
+        .insert(TaskCreate {
+            id: Uuid::new_v4(),
+            account_id,
+            title: title.to_string(),
+            description: None,
+            task_type,
+            subject: Subject::Math,
+            due_date: None,
+            auto_complete,
+            source: TaskSource::Manual,
+            created_at: Utc::now(),
+            created_by: account_id,
+        })
+        .await
+        .expect("创建任务")
+}
+
+/// 创建资源记录（不写入存储）
+pub async fn create_resource(
+    repos: &Repos,
+    account_id: Uuid,
+    file_type: FileType,
+    content_hash: &str,
+) -> Resource {
+    repos
+        .resource
+        .insert(
+            ResourceCreate {
+                id: Uuid::new_v4(),
+                account_id,
+                file_name: format!("作业.{}", file_type.as_str()),
+                file_type,
+                storage_url: format!("local://resources/{}/{}", account_id, content_hash),
+                content_hash: content_hash.to_string(),
+                file_size: 1024,
+                mime_type: match file_type {
+                    FileType::Pdf => "application/pdf",
+                    FileType::Image => "image/png",
+                    FileType::Doc => "application/msword",
+                    FileType::Other => "application/octet-stream",
+                }
+                .to_string(),
+                created_at: Utc::now(),
+                created_by: account_id,
+            },
+            i64::MAX,
+        )
+        .await
+        .expect("创建资源")
+}
+
+/// 临时目录下的本地存储
+pub async fn local_storage() -> Arc<dyn Storage> {
+    let root = std::env::temp_dir().join(format!("learnnest-test-{}", Uuid::new_v4()));
+    Arc::new(
+        LocalStorage::new(&root.to_string_lossy())
+            .await
+            .expect("创建存储目录"),
+    )
+}
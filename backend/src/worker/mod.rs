@@ -0,0 +1,197 @@
+use crate::app::context::AppContext;
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::model::job::{Job, JobPayload, JobStatus};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+// 超时任务的检查间隔上限（秒）
+const MAX_REAP_INTERVAL_SECS: u64 = 60;
+// 单个任务的执行时限占 stale_secs 的比例（千分比），保证 worker 先于回收中断任务
+const EXECUTE_TIMEOUT_PERMILLE: u64 = 800;
+
+/// 启动后台任务 worker 和超时任务回收；shutdown 置为 true 后，worker 执行完手上的任务即退出
+pub fn spawn_workers(
+    ctx: Arc<AppContext>,
+    config: &AppConfig,
+    shutdown: watch::Receiver<bool>,
+) -> Vec<JoinHandle<()>> {
+    if config.job.workers == 0 {
+        tracing::info!("JOB_WORKERS=0，本进程不执行后台任务");
+        return Vec::new();
+    }
+
+    let config = Arc::new(config.clone());
+    let mut handles: Vec<JoinHandle<()>> = (0..config.job.workers)
+        .map(|i| {
+            let worker_id = format!("worker-{}-{}", std::process::id(), i);
+            tokio::spawn(run_worker(
+                ctx.clone(),
+                config.clone(),
+                worker_id,
+                shutdown.clone(),
+            ))
+        })
+        .collect();
+    tracing::info!(workers = config.job.workers, "后台任务 worker 已启动");
+    handles.push(tokio::spawn(run_reaper(ctx, config, shutdown)));
+    handles
+}
+
+async fn run_worker(
+    ctx: Arc<AppContext>,
+    config: Arc<AppConfig>,
+    worker_id: String,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let idle = Duration::from_millis(config.job.poll_interval_ms);
+    while !*shutdown.borrow() {
+        match ctx.services.job.claim(&worker_id).await {
+            // 有任务时连续抢占，直到队列为空
+            Ok(Some(job)) => {
+                execute(&ctx, &config, &worker_id, job).await;
+                continue;
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!(err = ?e, %worker_id, "抢占任务失败"),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(idle) => {}
+            changed = shutdown.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    tracing::info!(%worker_id, "worker 已退出");
+}
+
+async fn execute(ctx: &AppContext, config: &AppConfig, worker_id: &str, job: Job) {
+    // 超过时限直接中断，避免回收后与重新抢占到的 worker 同时执行
+    let limit = Duration::from_millis(config.job.stale_secs * EXECUTE_TIMEOUT_PERMILLE);
+    let outcome = match serde_json::from_value::<JobPayload>(job.payload.clone()) {
+        Ok(payload) => tokio::time::timeout(limit, dispatch(ctx, config, payload))
+            .await
+            .unwrap_or_else(|_| {
+                Err(AppError::Upstream(format!(
+                    "任务执行超过 {} 秒，已中断",
+                    limit.as_secs()
+                )))
+            }),
+        Err(e) => Err(AppError::BadRequest(format!("任务参数无法解析: {}", e))),
+    };
+
+    match outcome {
+        Ok(result) => match ctx.services.job.succeed(job.id, worker_id, result).await {
+            Ok(()) => {}
+            Err(AppError::Conflict(_)) => {
+                tracing::warn!(job_id = %job.id, %worker_id, "任务已被回收，执行结果未记录")
+            }
+            Err(e) => tracing::error!(err = ?e, job_id = %job.id, "记录任务结果失败"),
+        },
+        Err(err) => {
+            // 参数错误、资源不存在等重试也不会成功，直接进入死信
+            let retryable = matches!(err, AppError::Internal | AppError::Upstream(_));
+            match ctx
+                .services
+                .job
+                .fail(
+                    &job,
+                    worker_id,
+                    &err.to_string(),
+                    retryable,
+                    config.job.retry_base_secs,
+                )
+                .await
+            {
+                Ok(JobStatus::Dead) => {
+                    tracing::error!(err = ?err, job_id = %job.id, kind = %job.kind, attempts = job.attempts, "任务失败，进入死信")
+                }
+                Ok(_) => {
+                    tracing::warn!(err = ?err, job_id = %job.id, kind = %job.kind, attempts = job.attempts, "任务失败，稍后重试")
+                }
+                Err(AppError::Conflict(_)) => {
+                    tracing::warn!(err = ?err, job_id = %job.id, %worker_id, "任务已被回收，失败状态未记录")
+                }
+                Err(e) => tracing::error!(err = ?e, job_id = %job.id, "记录任务失败状态失败"),
+            }
+        }
+    }
+}
+
+/// 按任务类型调用对应的 service，返回记录到 job.result 的结果
+async fn dispatch(
+    ctx: &AppContext,
+    config: &AppConfig,
+    payload: JobPayload,
+) -> Result<Option<Value>, AppError> {
+    match payload {
+        JobPayload::ExtractTasks {
+            resource_id,
+            account_id,
+        } => {
+            // 先取文字（已缓存时不重复识别），失败时图片仍可直接交给 LLM
+            if let Err(e) = ctx
+                .services
+                .ocr
+                .extract_text(resource_id, config.pdf_text_extractor.as_deref())
+                .await
+            {
+                tracing::warn!(err = ?e, %resource_id, "提取资源文字失败");
+            }
+            let drafts = ctx
+                .services
+                .extraction
+                .extract(resource_id, account_id)
+                .await?;
+            let draft_ids: Vec<_> = drafts.iter().map(|d| d.id).collect();
+            Ok(Some(json!({ "draft_ids": draft_ids })))
+        }
+        JobPayload::Thumbnail { resource_id } => {
+            ctx.services
+                .resource
+                .generate_thumbnail(resource_id, config.pdf_renderer.as_deref())
+                .await?;
+            Ok(None)
+        }
+        JobPayload::ExtractText { resource_id } => {
+            let text = ctx
+                .services
+                .ocr
+                .extract_text(resource_id, config.pdf_text_extractor.as_deref())
+                .await?;
+            Ok(text.map(|t| json!({ "chars": t.chars().count() })))
+        }
+    }
+}
+
+/// 定期回收执行超时的任务（worker 崩溃或进程被强制结束时遗留的 running 任务）：
+/// 还有重试次数的重新排队，否则进入死信
+async fn run_reaper(
+    ctx: Arc<AppContext>,
+    config: Arc<AppConfig>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let interval = Duration::from_secs(config.job.stale_secs.clamp(1, MAX_REAP_INTERVAL_SECS));
+    while !*shutdown.borrow() {
+        match ctx.services.job.requeue_stale(config.job.stale_secs).await {
+            Ok((0, 0)) => {}
+            Ok((requeued, dead)) => tracing::warn!(requeued, dead, "已回收超时任务"),
+            Err(e) => tracing::warn!(err = ?e, "回收超时任务失败"),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            changed = shutdown.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}